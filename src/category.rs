@@ -0,0 +1,21 @@
+//! The categories families are filed under in Google Fonts metadata.
+
+metadata_enum! {
+    /// A Google Fonts family category, as written in `METADATA.pb` (e.g.
+    /// `SANS_SERIF`).
+    ///
+    /// Anything other than the five catalog categories is kept as `Other`, so
+    /// parsing never fails.
+    pub enum Category {
+        /// `SERIF`: text faces with serifs.
+        Serif = "SERIF",
+        /// `SANS_SERIF`: text faces without serifs.
+        SansSerif = "SANS_SERIF",
+        /// `DISPLAY`: faces meant for headlines and large sizes.
+        Display = "DISPLAY",
+        /// `HANDWRITING`: script and hand-lettered faces.
+        Handwriting = "HANDWRITING",
+        /// `MONOSPACE`: fixed-width faces.
+        Monospace = "MONOSPACE",
+    }
+}
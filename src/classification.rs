@@ -0,0 +1,19 @@
+//! The broad classifications families declare in Google Fonts metadata.
+
+metadata_enum! {
+    /// A Google Fonts family classification, as written in `METADATA.pb`'s
+    /// `classifications` (e.g. `DISPLAY`).
+    ///
+    /// Anything other than the known classifications is kept as `Other`, so
+    /// parsing never fails.
+    pub enum Classification {
+        /// `DISPLAY`: suited to headlines and large sizes.
+        Display = "DISPLAY",
+        /// `HANDWRITING`: looks written by hand.
+        Handwriting = "HANDWRITING",
+        /// `MONOSPACE`: every glyph has the same advance width.
+        Monospace = "MONOSPACE",
+        /// `SYMBOLS`: icons, dingbats and other non-text glyphs.
+        Symbols = "SYMBOLS",
+    }
+}
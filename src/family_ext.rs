@@ -0,0 +1,274 @@
+//! Derived queries on a family's fonts.
+
+use crate::{
+    AxisSegmentProto, Category, Classification, FamilyProto, FontExt, FontProto, FontStyle, Subset,
+    filename_axes, registered_axis_default, write_family,
+};
+
+/// Common questions about a [`FamilyProto`], answered from its `fonts`.
+pub trait FamilyExt {
+    /// The distinct weights of the family's fonts, in ascending order.
+    fn weights(&self) -> Vec<i32>;
+
+    /// True if any font in the family has the `italic` style.
+    fn has_italic(&self) -> bool;
+
+    /// True if any font is a variable font, judged by a filename such as
+    /// `Roboto[wdth,wght].ttf`.
+    fn is_variable(&self) -> bool;
+
+    /// The distinct styles of the family's fonts, in order of first appearance.
+    fn styles(&self) -> Vec<&str>;
+
+    /// The family's declared axis with the given tag, e.g. `wght`.
+    ///
+    /// `METADATA.pb` declares axes as [`AxisSegmentProto`]s (tag and range);
+    /// the registry's [`AxisProto`](crate::AxisProto) isn't part of a family.
+    fn axis(&self, tag: &str) -> Option<&AxisSegmentProto>;
+
+    /// The declared `(min, max)` range of an axis, from the family's `axes`.
+    fn axis_range(&self, tag: &str) -> Option<(f32, f32)>;
+
+    /// Each declared axis as `(tag, min, default, max)`, in declaration order.
+    ///
+    /// The default is the [`effective_axis_default`](Self::effective_axis_default)
+    /// clamped to the declared range, or the minimum for unregistered axes
+    /// without an override. Families that declare no axes yield nothing, even
+    /// if their filenames suggest a variable font.
+    fn axes_summary(&self) -> Vec<(&str, f32, f32, f32)>;
+
+    /// The default for an axis: the family's `registry_default_overrides`
+    /// entry if it has one, otherwise the registry default (see
+    /// [`registered_axis_default`]).
+    fn effective_axis_default(&self, tag: &str) -> Option<f32>;
+
+    /// The lowest and highest weight the family offers.
+    ///
+    /// If a font's filename encodes a `wght` axis and the family declares
+    /// one, the declared range is used; otherwise the range spans the fonts'
+    /// `weight` values. Returns `None` for a family with no fonts.
+    fn weight_range(&self) -> Option<(i32, i32)>;
+
+    /// The family's declared subsets, sorted and deduplicated.
+    fn subsets_sorted(&self) -> Vec<&str>;
+
+    /// True if the family declares `subset`, e.g. `latin-ext`.
+    fn has_subset(&self, subset: &str) -> bool;
+
+    /// The family's declared subsets as [`Subset`]s, in declaration order.
+    fn typed_subsets(&self) -> Vec<Subset>;
+
+    /// True if the family offers exactly this style and weight; see
+    /// [`FamilyExt::font_for`].
+    fn has_font(&self, style: FontStyle, weight: i32) -> bool;
+
+    /// The font that renders exactly this style and weight, if any.
+    ///
+    /// A static font of that style and weight is preferred; otherwise a
+    /// variable font of that style qualifies if the family's declared `wght`
+    /// axis covers the weight. Unlike [`select_font`](crate::select_font)
+    /// this never settles for the nearest match.
+    fn font_for(&self, style: FontStyle, weight: i32) -> Option<&FontProto>;
+
+    /// The names credited in the `designer` field, in order.
+    ///
+    /// The field is a comma-separated list; names are trimmed and empty entries
+    /// dropped.
+    fn designer_names(&self) -> Vec<String>;
+
+    /// The copyright holder most fonts name; see [`FontExt::copyright_holder`].
+    ///
+    /// On a tie the holder of the earlier font wins.
+    fn copyright_holder(&self) -> Option<&str>;
+
+    /// The family's `category` entries, parsed.
+    fn categories(&self) -> Vec<Category>;
+
+    /// The family's `classifications` entries, parsed.
+    fn classifications_typed(&self) -> Vec<Classification>;
+
+    /// A fingerprint of the family's metadata, for keying caches.
+    ///
+    /// This is the 64-bit FNV-1a hash of [`write_family`]'s output, which
+    /// writes map entries in key order, so it is stable across runs and
+    /// platforms, ignores field order and whitespace in the source file, and
+    /// is equal for families that write identically.
+    fn content_hash(&self) -> u64;
+
+    /// The fonts in the order a specimen page shows them: normal before
+    /// italic (other styles last), then variable fonts before static ones,
+    /// then ascending weight. Fonts that compare equal keep their order.
+    fn fonts_display_order(&self) -> Vec<&FontProto>;
+}
+
+impl FamilyExt for FamilyProto {
+    fn weights(&self) -> Vec<i32> {
+        let mut weights: Vec<_> = self.fonts.iter().map(|f| f.weight()).collect();
+        weights.sort();
+        weights.dedup();
+        weights
+    }
+
+    fn has_italic(&self) -> bool {
+        self.fonts.iter().any(|f| f.style() == "italic")
+    }
+
+    fn is_variable(&self) -> bool {
+        self.fonts.iter().any(FontExt::is_variable)
+    }
+
+    fn styles(&self) -> Vec<&str> {
+        let mut styles = Vec::new();
+        for style in self.fonts.iter().map(|f| f.style()) {
+            if !styles.contains(&style) {
+                styles.push(style);
+            }
+        }
+        styles
+    }
+
+    fn axis(&self, tag: &str) -> Option<&AxisSegmentProto> {
+        self.axes.iter().find(|a| a.tag() == tag)
+    }
+
+    fn axis_range(&self, tag: &str) -> Option<(f32, f32)> {
+        self.axis(tag).map(|a| (a.min_value(), a.max_value()))
+    }
+
+    fn axes_summary(&self) -> Vec<(&str, f32, f32, f32)> {
+        self.axes
+            .iter()
+            .map(|a| {
+                let (min, max) = (a.min_value(), a.max_value());
+                let default = self
+                    .effective_axis_default(a.tag())
+                    .map(|d| d.max(min).min(max))
+                    .unwrap_or(min);
+                (a.tag(), min, default, max)
+            })
+            .collect()
+    }
+
+    fn effective_axis_default(&self, tag: &str) -> Option<f32> {
+        self.registry_default_overrides
+            .get(tag)
+            .copied()
+            .or_else(|| registered_axis_default(tag))
+    }
+
+    fn weight_range(&self) -> Option<(i32, i32)> {
+        let variable_wght = self
+            .fonts
+            .iter()
+            .any(|f| filename_axes(f).contains(&"wght"));
+        if let (true, Some((min, max))) = (variable_wght, self.axis_range("wght")) {
+            return Some((min as i32, max as i32));
+        }
+        let weights = self.weights();
+        Some((*weights.first()?, *weights.last()?))
+    }
+
+    fn subsets_sorted(&self) -> Vec<&str> {
+        let mut subsets: Vec<_> = self.subsets.iter().map(String::as_str).collect();
+        subsets.sort();
+        subsets.dedup();
+        subsets
+    }
+
+    fn has_subset(&self, subset: &str) -> bool {
+        self.subsets.iter().any(|s| s == subset)
+    }
+
+    fn typed_subsets(&self) -> Vec<Subset> {
+        self.subsets
+            .iter()
+            .map(|s| {
+                let Ok(subset) = s.parse::<Subset>();
+                subset
+            })
+            .collect()
+    }
+
+    fn has_font(&self, style: FontStyle, weight: i32) -> bool {
+        self.font_for(style, weight).is_some()
+    }
+
+    fn font_for(&self, style: FontStyle, weight: i32) -> Option<&FontProto> {
+        let of_style = || {
+            self.fonts
+                .iter()
+                .filter(move |f| f.style() == style.style())
+        };
+        of_style()
+            .find(|f| !f.is_variable() && f.weight() == weight)
+            .or_else(|| {
+                let covered = self
+                    .axis_range("wght")
+                    .is_some_and(|(min, max)| (min..=max).contains(&(weight as f32)));
+                of_style().find(|f| covered && f.is_variable())
+            })
+    }
+
+    fn designer_names(&self) -> Vec<String> {
+        self.designer()
+            .split(',')
+            .map(str::trim)
+            .filter(|n| !n.is_empty())
+            .map(str::to_string)
+            .collect()
+    }
+
+    fn copyright_holder(&self) -> Option<&str> {
+        let holders: Vec<&str> = self
+            .fonts
+            .iter()
+            .filter_map(|f| f.copyright_holder())
+            .collect();
+        holders
+            .iter()
+            .copied()
+            .rev()
+            .max_by_key(|h| holders.iter().filter(|other| *other == h).count())
+    }
+
+    fn categories(&self) -> Vec<Category> {
+        self.category
+            .iter()
+            .map(|c| {
+                let Ok(category) = c.parse::<Category>();
+                category
+            })
+            .collect()
+    }
+
+    fn classifications_typed(&self) -> Vec<Classification> {
+        self.classifications
+            .iter()
+            .map(|c| {
+                let Ok(classification) = c.parse::<Classification>();
+                classification
+            })
+            .collect()
+    }
+
+    fn content_hash(&self) -> u64 {
+        write_family(self)
+            .bytes()
+            .fold(0xcbf2_9ce4_8422_2325, |hash, b| {
+                (hash ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3)
+            })
+    }
+
+    fn fonts_display_order(&self) -> Vec<&FontProto> {
+        let mut fonts: Vec<_> = self.fonts.iter().collect();
+        fonts.sort_by_key(|f| {
+            let style = match f.style() {
+                "normal" => 0,
+                "italic" => 1,
+                _ => 2,
+            };
+            (style, !f.is_variable(), f.weight())
+        });
+        fonts
+    }
+}
@@ -0,0 +1,62 @@
+//! Derived queries on a single font.
+
+use crate::{FontProto, filename_axes};
+
+/// Common questions about a [`FontProto`], answered from its fields.
+pub trait FontExt {
+    /// The rights holder named in the font's `copyright`, e.g. "The Roboto
+    /// Project Authors".
+    ///
+    /// The leading "Copyright", "(c)" or "©" and years are skipped, and the
+    /// holder ends at a parenthesised URL or email, a comma, a semicolon or a
+    /// sentence break, so all of these give "Jane Doe":
+    ///
+    /// - `Copyright 2011 Jane Doe (https://example.com)`
+    /// - `Copyright (c) 2010-2015, Jane Doe <jane@example.com>, with Reserved Font Name "Sans"`
+    /// - `© 2019 by Jane Doe. All rights reserved.`
+    ///
+    /// Returns `None` if no holder can be found.
+    fn copyright_holder(&self) -> Option<&str>;
+
+    /// Whether this is a variable font, going by the axis tags in its
+    /// filename (`Roboto[wdth,wght].ttf`); see [`filename_axes`].
+    fn is_variable(&self) -> bool;
+}
+
+/// Strip `prefix` from the start of `s`, ignoring ASCII case.
+fn strip_prefix_ci<'a>(s: &'a str, prefix: &str) -> Option<&'a str> {
+    s.get(..prefix.len())
+        .filter(|p| p.eq_ignore_ascii_case(prefix))
+        .map(|_| &s[prefix.len()..])
+}
+
+impl FontExt for FontProto {
+    fn is_variable(&self) -> bool {
+        !filename_axes(self).is_empty()
+    }
+
+    fn copyright_holder(&self) -> Option<&str> {
+        let mut s = self.copyright();
+        loop {
+            s = s.trim_start_matches(|c: char| c.is_whitespace() || matches!(c, ',' | ':' | '.'));
+            if let Some(rest) = strip_prefix_ci(s, "copyright")
+                .or_else(|| strip_prefix_ci(s, "(c)"))
+                .or_else(|| s.strip_prefix('©'))
+                .or_else(|| strip_prefix_ci(s, "by "))
+            {
+                s = rest;
+            } else if s.starts_with(|c: char| c.is_ascii_digit()) {
+                s = s.trim_start_matches(|c: char| c.is_ascii_digit() || matches!(c, '-' | '–'));
+            } else {
+                break;
+            }
+        }
+        let end = [" (", " <", ",", ";", ". "]
+            .iter()
+            .filter_map(|sep| s.find(sep))
+            .min()
+            .unwrap_or(s.len());
+        let holder = s[..end].trim().trim_end_matches('.').trim_end();
+        (!holder.is_empty()).then_some(holder)
+    }
+}
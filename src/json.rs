@@ -0,0 +1,197 @@
+//! `serde` serialization of the metadata protos, behind the `serde` feature.
+//!
+//! Serialization goes through protobuf's JSON mapping with the proto field
+//! names as keys, so the output matches [`GoogleFonts::write_families_jsonl`](crate::GoogleFonts::write_families_jsonl).
+
+use std::{
+    io::{Error, ErrorKind, Read, Write},
+    str::FromStr,
+    time::{Duration, SystemTime},
+};
+
+use protobuf::MessageFull;
+use protobuf_json_mapping::PrintOptions;
+use serde::{Serialize, Serializer, ser::Error as _};
+use serde_json::{Value, json};
+
+use crate::{
+    AxisProto, FamilyEntry, FamilyProto, FontProto, GoogleFonts, ParseReport, TagMetadata, Tagging,
+    load_family,
+};
+
+/// Convert a message to JSON with snake_case (proto field name) keys.
+fn proto_to_json<M: MessageFull>(message: &M) -> Result<serde_json::Value, String> {
+    let options = PrintOptions {
+        proto_field_name: true,
+        ..Default::default()
+    };
+    let json = protobuf_json_mapping::print_to_string_with_options(message, &options)
+        .map_err(|e| e.to_string())?;
+    serde_json::from_str(&json).map_err(|e| e.to_string())
+}
+
+macro_rules! serialize_via_json {
+    ($($proto:ty),*) => {
+        $(
+            impl Serialize for $proto {
+                fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    proto_to_json(self)
+                        .map_err(S::Error::custom)?
+                        .serialize(serializer)
+                }
+            }
+        )*
+    };
+}
+
+serialize_via_json!(FamilyProto, FontProto, AxisProto);
+
+/// Convert a family to a JSON value, e.g. to embed in a larger document.
+///
+/// Keys are the proto field names (`post_script_name`, `date_added`, ...)
+/// and unset fields are omitted.
+pub fn family_to_json(family: &FamilyProto) -> serde_json::Value {
+    proto_to_json(family).expect("FamilyProto always maps to JSON")
+}
+
+impl ParseReport {
+    /// The report as JSON: `{"parsed": 1, "failed": 1, "failures": [{"path":
+    /// "...", "error": "..."}]}`.
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "parsed": self.parsed,
+            "failed": self.failed,
+            "failures": self
+                .failures
+                .iter()
+                .map(|(path, error)| {
+                    serde_json::json!({ "path": path.to_string_lossy(), "error": error })
+                })
+                .collect::<Vec<_>>(),
+        })
+    }
+}
+
+fn invalid_data(e: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> Error {
+    Error::new(ErrorKind::InvalidData, e)
+}
+
+/// Parse a list of CSV lines, as written by `dump_index`, back into values.
+fn parse_lines<T: FromStr>(value: &Value) -> Result<Option<Vec<T>>, Error>
+where
+    T::Err: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    let Some(lines) = value.as_array() else {
+        return Ok(None);
+    };
+    lines
+        .iter()
+        .map(|line| {
+            line.as_str()
+                .ok_or_else(|| invalid_data("expected a CSV line"))?
+                .parse()
+                .map_err(invalid_data)
+        })
+        .collect::<Result<_, _>>()
+        .map(Some)
+}
+
+impl GoogleFonts {
+    /// Write the families, tags and tag metadata read from the repository as
+    /// one JSON document, for [`GoogleFonts::load_index`] to read back on a
+    /// later run without rescanning.
+    ///
+    /// Families are stored with paths relative to the repository root.
+    /// Families that failed to parse are stored by path only and are read
+    /// again on load; tags or tag metadata that couldn't be read are left
+    /// out and read again on first use.
+    pub fn dump_index<W: Write>(&self, w: W) -> Result<(), Error> {
+        let families = self
+            .families()
+            .iter()
+            .map(|entry| {
+                let path = entry
+                    .path
+                    .strip_prefix(&self.repo_dir)
+                    .unwrap_or(&entry.path);
+                let family = entry.family().map(proto_to_json).transpose();
+                Ok(json!({
+                    "path": path.to_string_lossy(),
+                    "family": family.map_err(invalid_data)?,
+                }))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        let lines = |items: Option<Vec<String>>| items.map_or(Value::Null, Value::from);
+        let tags = self
+            .tags()
+            .ok()
+            .map(|tags| tags.iter().map(Tagging::to_string).collect());
+        let tag_metadata = self
+            .tag_metadata()
+            .ok()
+            .map(|metadata| metadata.iter().map(TagMetadata::to_string).collect());
+        let loaded_at = self
+            .loaded_at
+            .get()
+            .and_then(|t| t.duration_since(SystemTime::UNIX_EPOCH).ok())
+            .map(|d| d.as_nanos() as u64);
+        serde_json::to_writer(
+            w,
+            &json!({
+                "loaded_at": loaded_at,
+                "families": families,
+                "tags": lines(tags),
+                "tag_metadata": lines(tag_metadata),
+            }),
+        )
+        .map_err(Error::from)
+    }
+
+    /// Populate the caches from a document written by
+    /// [`GoogleFonts::dump_index`] instead of scanning the repository.
+    ///
+    /// The family filter is applied to the stored paths. Caches that are
+    /// already populated are kept, and every other index is rebuilt from
+    /// the loaded data on first use. [`GoogleFonts::reload_if_changed`]
+    /// compares against the time the dumped data was originally read, so
+    /// an index that has gone stale is noticed.
+    pub fn load_index<R: Read>(&self, r: R) -> Result<(), Error> {
+        let index: Value = serde_json::from_reader(r)?;
+        let families = index["families"]
+            .as_array()
+            .ok_or_else(|| invalid_data("index has no families"))?
+            .iter()
+            .filter_map(|entry| {
+                let path = entry["path"].as_str()?;
+                let keep = self.family_filter.as_ref().is_none_or(|r| r.is_match(path));
+                keep.then(|| (self.repo_dir.join(path), &entry["family"]))
+            })
+            .map(|(path, family)| {
+                if family.is_null() {
+                    return Ok(load_family(path));
+                }
+                let family = protobuf_json_mapping::parse_from_str(&family.to_string())
+                    .map_err(invalid_data)?;
+                Ok(FamilyEntry {
+                    path,
+                    family: Ok(family),
+                })
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        let tags = parse_lines::<Tagging>(&index["tags"])?;
+        let tag_metadata = parse_lines::<TagMetadata>(&index["tag_metadata"])?;
+        if let Some(nanos) = index["loaded_at"].as_u64() {
+            let _ = self
+                .loaded_at
+                .set(SystemTime::UNIX_EPOCH + Duration::from_nanos(nanos));
+        }
+        let _ = self.families.set(families);
+        if let Some(tags) = tags {
+            let _ = self.tags.set(Ok(tags));
+        }
+        if let Some(tag_metadata) = tag_metadata {
+            let _ = self.tag_metadata.set(Ok(tag_metadata));
+        }
+        Ok(())
+    }
+}
@@ -1,76 +1,699 @@
+#[macro_use]
+mod metadata_enum;
 mod axes;
+mod category;
+mod classification;
 mod designers;
+mod family_ext;
+mod font_ext;
 mod fonts_public;
+#[cfg(feature = "serde")]
+mod json;
+mod registry;
+mod subsets;
 
 use std::{
+    borrow::Cow,
     cell::OnceCell,
-    collections::HashMap,
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    fmt::{self, Display},
     fs::{self, File},
-    io::{BufRead, BufReader, Error, ErrorKind},
+    io::{BufRead, BufReader, Error, ErrorKind, Read, Write},
+    ops::Range,
     path::{Path, PathBuf},
     str::FromStr,
+    thread,
+    time::SystemTime,
 };
 
 pub use axes::{AxisProto, FallbackProto};
+pub use category::Category;
+pub use classification::Classification;
 pub use designers::{AvatarProto, DesignerInfoProto};
+pub use family_ext::FamilyExt;
+pub use font_ext::FontExt;
 pub use fonts_public::*;
-use google_fonts_languages::LANGUAGES;
 pub use google_fonts_languages::{
     ExemplarCharsProto, LanguageProto, RegionProto, SampleTextProto, ScriptProto,
 };
+use google_fonts_languages::{LANGUAGES, REGIONS, SCRIPTS};
+#[cfg(feature = "serde")]
+pub use json::family_to_json;
+use protobuf::reflect::{MessageDescriptor, RuntimeFieldType, RuntimeType};
 use protobuf::text_format::ParseError;
+use protobuf_json_mapping::PrintOptions;
 use regex::Regex;
+pub use registry::{RegisteredAxis, registered_axis, registered_axis_default};
+pub use subsets::{Subset, subset_script, subset_unicode_range};
 use walkdir::WalkDir;
 
 /// Read a FamilyProto from a METADATA.pb file content.
 ///
-/// This function handles undocumented fields by stripping them out before parsing.
+/// This function handles undocumented fields (such as `position` inside
+/// `fonts`) by skipping any field its enclosing message doesn't declare
+/// before parsing. Legacy field names, at any depth, are first renamed to their
+/// current spelling; see [`LEGACY_FIELD_NAMES`].
 pub fn read_family(s: &str) -> Result<FamilyProto, ParseError> {
-    if s.contains("position") {
-        let re = Regex::new(r"(?m)position\s+\{[^}]*\}").expect("Valid re");
-        let s = re.replace_all(s, "");
-        protobuf::text_format::parse_from_str(&s)
-    } else {
-        protobuf::text_format::parse_from_str(s)
+    let descriptor = <FamilyProto as protobuf::MessageFull>::descriptor();
+    let s = rename_legacy_fields(s);
+    protobuf::text_format::parse_from_str(&strip_unknown_fields(&s, &descriptor))
+}
+
+/// Field names found in older or converted `METADATA.pb` files, and the
+/// current name [`read_family`] reads them as.
+///
+/// These are the camelCase spellings carried over from `METADATA.json` and
+/// the unsplit `postscript_name`.
+pub const LEGACY_FIELD_NAMES: [(&str, &str); 11] = [
+    ("postScriptName", "post_script_name"),
+    ("postscript_name", "post_script_name"),
+    ("fullName", "full_name"),
+    ("dateAdded", "date_added"),
+    ("displayName", "display_name"),
+    ("primaryScript", "primary_script"),
+    ("primaryLanguage", "primary_language"),
+    ("isNoto", "is_noto"),
+    ("minisiteUrl", "minisite_url"),
+    ("minValue", "min_value"),
+    ("maxValue", "max_value"),
+];
+
+/// Rename [`LEGACY_FIELD_NAMES`] wherever they are used as field names, that
+/// is followed by `:` or an opening brace. Strings and comments are left
+/// alone, and input without legacy names is returned unchanged.
+fn rename_legacy_fields(s: &str) -> Cow<'_, str> {
+    let b = s.as_bytes();
+    let mut renamed = Vec::new();
+    let mut i = 0;
+    while i < b.len() {
+        match b[i] {
+            quote @ (b'"' | b'\'') => {
+                i += 1;
+                while i < b.len() && b[i] != quote {
+                    i += if b[i] == b'\\' { 2 } else { 1 };
+                }
+                i += 1;
+            }
+            b'#' => {
+                while i < b.len() && b[i] != b'\n' {
+                    i += 1;
+                }
+            }
+            c if c.is_ascii_alphabetic() || c == b'_' => {
+                let start = i;
+                while i < b.len() && (b[i].is_ascii_alphanumeric() || b[i] == b'_') {
+                    i += 1;
+                }
+                let mut j = i;
+                while j < b.len() && b[j].is_ascii_whitespace() {
+                    j += 1;
+                }
+                let is_field = matches!(b.get(j), Some(b':' | b'{' | b'<'));
+                if let Some((_, current)) = LEGACY_FIELD_NAMES
+                    .iter()
+                    .find(|(legacy, _)| is_field && *legacy == &s[start..i])
+                {
+                    renamed.push((start..i, *current));
+                }
+            }
+            _ => i += 1,
+        }
+    }
+    if renamed.is_empty() {
+        return Cow::Borrowed(s);
+    }
+    let mut out = String::with_capacity(s.len());
+    let mut last = 0;
+    for (range, current) in renamed {
+        out.push_str(&s[last..range.start]);
+        out.push_str(current);
+        last = range.end;
+    }
+    out.push_str(&s[last..]);
+    Cow::Owned(out)
+}
+
+/// Parse METADATA.pb content into a FamilyProto.
+///
+/// An alias for [`read_family`], kept for consumers written against that
+/// name.
+pub fn parse_from_str(s: &str) -> Result<FamilyProto, ParseError> {
+    read_family(s)
+}
+
+/// Read a FamilyProto from METADATA.pb content in a reader.
+///
+/// The outer `Result` reports failure to read (including invalid UTF-8); the
+/// inner one is the outcome of parsing, as for [`read_family`].
+pub fn read_family_from_reader<R: Read>(
+    mut r: R,
+) -> Result<Result<FamilyProto, ParseError>, Error> {
+    let mut s = String::new();
+    r.read_to_string(&mut s)?;
+    Ok(read_family(&s))
+}
+
+/// Read a FamilyProto from METADATA.pb content held as bytes.
+///
+/// The content must be UTF-8; otherwise this fails with
+/// [`ErrorKind::InvalidData`].
+pub fn read_family_bytes(b: &[u8]) -> Result<Result<FamilyProto, ParseError>, Error> {
+    let s = std::str::from_utf8(b).map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+    Ok(read_family(s))
+}
+
+/// Remove text format fields that `descriptor`'s message doesn't declare.
+///
+/// Known message fields written as blocks (`name { ... }`) are descended
+/// into and checked against their own message type, so unknown fields are
+/// dropped at any depth. Unknown fields may be scalars (`name: value`), lists
+/// (`name: [...]`) or messages, with any nesting inside. Strings and comments
+/// are skipped over so braces inside them don't count.
+fn strip_unknown_fields<'a>(s: &'a str, descriptor: &MessageDescriptor) -> Cow<'a, str> {
+    fn skip_string(b: &[u8], mut i: usize) -> usize {
+        let quote = b[i];
+        i += 1;
+        while i < b.len() && b[i] != quote {
+            i += if b[i] == b'\\' { 2 } else { 1 };
+        }
+        (i + 1).min(b.len())
+    }
+    fn skip_space(b: &[u8], mut i: usize) -> usize {
+        while i < b.len() {
+            match b[i] {
+                b'#' => {
+                    while i < b.len() && b[i] != b'\n' {
+                        i += 1;
+                    }
+                }
+                c if c.is_ascii_whitespace() => i += 1,
+                _ => break,
+            }
+        }
+        i
+    }
+    fn skip_block(b: &[u8], mut i: usize) -> usize {
+        let mut depth = 0;
+        while i < b.len() {
+            match b[i] {
+                b'"' | b'\'' => {
+                    i = skip_string(b, i);
+                    continue;
+                }
+                b'#' => {
+                    i = skip_space(b, i);
+                    continue;
+                }
+                b'{' | b'<' | b'[' => depth += 1,
+                b'}' | b'>' | b']' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return i + 1;
+                    }
+                }
+                _ => {}
+            }
+            i += 1;
+        }
+        i
+    }
+    fn skip_value(b: &[u8], mut i: usize) -> usize {
+        i = skip_space(b, i);
+        if i < b.len() && b[i] == b':' {
+            i = skip_space(b, i + 1);
+        }
+        match b.get(i) {
+            Some(b'{' | b'<' | b'[') => skip_block(b, i),
+            Some(b'"' | b'\'') => {
+                // Adjacent string literals are concatenated
+                while matches!(b.get(i), Some(b'"' | b'\'')) {
+                    i = skip_space(b, skip_string(b, i));
+                }
+                i
+            }
+            _ => {
+                while i < b.len() && !b[i].is_ascii_whitespace() && !b"#;,}".contains(&b[i]) {
+                    i += 1;
+                }
+                i
+            }
+        }
+    }
+
+    /// Strip the fields of one message starting at `i`, up to its closing
+    /// brace or the end of input; returns where that is.
+    fn strip_fields(
+        s: &str,
+        mut i: usize,
+        descriptor: &MessageDescriptor,
+        removed: &mut Vec<Range<usize>>,
+    ) -> usize {
+        let b = s.as_bytes();
+        loop {
+            i = skip_space(b, i);
+            if i >= b.len() || matches!(b[i], b'}' | b'>') {
+                return i;
+            }
+            let start = i;
+            while i < b.len() && (b[i].is_ascii_alphanumeric() || b[i] == b'_') {
+                i += 1;
+            }
+            if i == start {
+                // Not a field name; let the parser report whatever this is
+                return b.len();
+            }
+            let field = descriptor.field_by_name(&s[start..i]);
+            let nested = field.as_ref().and_then(|f| match f.runtime_field_type() {
+                RuntimeFieldType::Singular(RuntimeType::Message(m))
+                | RuntimeFieldType::Repeated(RuntimeType::Message(m)) => Some(m),
+                _ => None,
+            });
+            let mut j = skip_space(b, i);
+            if b.get(j) == Some(&b':') {
+                j = skip_space(b, j + 1);
+            }
+            i = match nested {
+                Some(message) if matches!(b.get(j), Some(b'{' | b'<')) => {
+                    (strip_fields(s, j + 1, &message, removed) + 1).min(b.len())
+                }
+                _ => skip_value(b, i),
+            };
+            i = skip_space(b, i);
+            if matches!(b.get(i), Some(b';' | b',')) {
+                i += 1;
+            }
+            if field.is_none() {
+                removed.push(start..i);
+            }
+        }
+    }
+
+    let mut removed = Vec::new();
+    strip_fields(s, 0, descriptor, &mut removed);
+    if removed.is_empty() {
+        return Cow::Borrowed(s);
+    }
+    let mut out = String::with_capacity(s.len());
+    let mut last = 0;
+    for range in removed {
+        out.push_str(&s[last..range.start]);
+        last = range.end;
+    }
+    out.push_str(&s[last..]);
+    Cow::Owned(out)
+}
+
+/// Write a FamilyProto as METADATA.pb text format.
+///
+/// The output uses protobuf's text format with two-space indentation and
+/// fields in declaration order, as the Google Fonts repo does, and omits
+/// unset and empty repeated fields. Map entries (`registry_default_overrides`,
+/// `sample_glyphs`) are written in key order, so the same family always
+/// writes the same text. `read_family` on the result yields an equal
+/// `FamilyProto`.
+pub fn write_family(family: &FamilyProto) -> String {
+    let text = protobuf::text_format::print_to_string_pretty(family);
+    let map_headers: Vec<String> = <FamilyProto as protobuf::MessageFull>::descriptor()
+        .fields()
+        .filter(|f| matches!(f.runtime_field_type(), RuntimeFieldType::Map(..)))
+        .map(|f| format!("{} {{", f.name()))
+        .collect();
+    let lines: Vec<&str> = text.lines().collect();
+    let mut out = String::with_capacity(text.len());
+    let mut i = 0;
+    while i < lines.len() {
+        let header = lines[i];
+        if !map_headers.iter().any(|h| h == header) {
+            out.push_str(header);
+            out.push('\n');
+            i += 1;
+            continue;
+        }
+        // The printer walks the map in hash order, writing each entry as its
+        // header, key, value and closing brace; the key line sorts them.
+        let mut entries = Vec::new();
+        while i < lines.len() && lines[i] == header {
+            let end = (i + 4).min(lines.len());
+            entries.push(lines[i..end].join("\n"));
+            i = end;
+        }
+        entries.sort();
+        for entry in entries {
+            out.push_str(&entry);
+            out.push('\n');
+        }
     }
+    out
 }
 
-fn exemplar_score(font: &FontProto, preferred_style: FontStyle, preferred_weight: i32) -> i32 {
+fn exemplar_score(
+    font: &FontProto,
+    preferred_style: FontStyle,
+    preferred_weight: i32,
+    preferred_width: i32,
+) -> i32 {
+    // Scores are in units of half a weight step, so every weight difference
+    // counts; the other preferences are scaled to match.
     let mut score = 0;
     // prefer preferred_style
     if font.style() == preferred_style.style() {
-        score += 16;
+        score += 3200;
     }
 
     // prefer closer to preferred_weight
-    score -= (font.weight() - preferred_weight).abs() / 100;
+    score -= 2 * (font.weight() - preferred_weight).abs();
 
-    // prefer more weight to less weight
+    // prefer more weight to less weight, between equally close weights
     if font.weight() > preferred_weight {
         score += 1;
     }
 
+    // prefer closer to preferred_width; a wdth axis covers every width
+    if !filename_axes(font).contains(&"wdth") {
+        score -= 20 * (static_font_width(font) as i32 - preferred_width).abs();
+    }
+
     // prefer variable
-    if font.filename().contains("].") {
-        score += 2;
+    if font.is_variable() {
+        score += 400;
     }
 
     score
 }
 
+/// The highest scoring font, ties going to the lexically smallest filename so
+/// the choice doesn't depend on the order fonts are listed in.
+fn best_scoring<'a>(
+    fonts: impl Iterator<Item = &'a FontProto>,
+    score: impl Fn(&FontProto) -> i32,
+) -> Option<&'a FontProto> {
+    fonts.max_by(|a, b| {
+        score(a)
+            .cmp(&score(b))
+            .then_with(|| b.filename().cmp(a.filename()))
+    })
+}
+
 /// Pick the exemplar font from a family.
 ///
 /// This is the font file that is most likely to be a representative choice for
 /// the family. The heuristic is to prefer normal style, weight as close to 400
-/// as possible, and a variable font if present.
+/// as possible, and a variable font if present. Equally good fonts are
+/// decided by filename, so the result doesn't depend on font order. Returns
+/// `None` only for a family with no fonts.
 pub fn exemplar(family: &FamilyProto) -> Option<&FontProto> {
-    fn score(font: &FontProto) -> i32 {
-        exemplar_score(font, FontStyle::Normal, 400)
+    best_scoring(family.fonts.iter(), |font| {
+        exemplar_score(font, FontStyle::Normal, 400, 100)
+    })
+}
+
+/// Pick the exemplar font of a given style from a family.
+///
+/// Only fonts of `style` are considered, scored as for [`exemplar`] with a
+/// target weight of 400. Returns `None` if the family has no font of that
+/// style, rather than settling for another style.
+pub fn exemplar_for_style(family: &FamilyProto, style: FontStyle) -> Option<&FontProto> {
+    best_scoring(
+        family.fonts.iter().filter(|f| f.style() == style.style()),
+        |font| exemplar_score(font, style, 400, 100),
+    )
+}
+
+/// Pick the best font of each style in a family.
+///
+/// Fonts are grouped by `style()` and each group's best font is chosen as
+/// by [`exemplar_for_style`]. Styles appear in the order they are first
+/// seen in the family.
+pub fn best_per_style(family: &FamilyProto) -> Vec<&FontProto> {
+    let mut best: Vec<&FontProto> = Vec::new();
+    for font in family.fonts.iter() {
+        let Some(i) = best.iter().position(|b| b.style() == font.style()) else {
+            best.push(font);
+            continue;
+        };
+        // Any style other than italic is scored as normal
+        let style = if font.style() == "italic" {
+            FontStyle::Italic
+        } else {
+            FontStyle::Normal
+        };
+        best[i] = best_scoring([best[i], font].into_iter(), |f| {
+            exemplar_score(f, style, 400, 100)
+        })
+        .unwrap();
     }
-    family
-        .fonts
+    best
+}
+
+/// Pick the font a menu should use to show a family: its Regular.
+///
+/// A static normal-style font of weight 400 wins outright. Otherwise the
+/// normal-style variable font is used if the family's `wght` axis (or lack
+/// of one) puts the default instance at 400: the family's effective `wght`
+/// default (see [`FamilyExt::effective_axis_default`]), clamped to the
+/// declared range, must be 400. Unlike
+/// [`exemplar`], an exact static cut is preferred over a variable file, and
+/// no other weight or style is settled for.
+pub fn default_instance(family: &FamilyProto) -> Option<&FontProto> {
+    let normal = || family.fonts.iter().filter(|f| f.style() == "normal");
+    normal()
+        .find(|f| !f.is_variable() && f.weight() == 400)
+        .or_else(|| {
+            let default_is_400 = family.axis_range("wght").is_none_or(|(min, max)| {
+                family
+                    .effective_axis_default("wght")
+                    .is_some_and(|d| d.max(min).min(max) == 400.0)
+            });
+            normal().find(|f| default_is_400 && f.is_variable())
+        })
+}
+
+/// Pick the font the catalog renders a family's name in.
+///
+/// This is the [`default_instance`], falling back to the [`exemplar`] for
+/// families without a Regular. Whether the family ships a `menu` subset for
+/// this purpose can be checked with [`FamilyExt::has_subset`]`("menu")`.
+pub fn menu_font(family: &FamilyProto) -> Option<&FontProto> {
+    default_instance(family).or_else(|| exemplar(family))
+}
+
+/// List the named weights a font offers, as `(weight, name)` pairs, for a
+/// weight picker.
+///
+/// A variable font offers every registered `wght` fallback (Thin, Light,
+/// Regular, ...) inside the family's declared `wght` range, lightest first.
+/// Any other font offers just its own weight, named after the nearest
+/// fallback. Italic names gain an "Italic" suffix, with "Regular Italic"
+/// shortened to "Italic".
+pub fn named_instances(family: &FamilyProto, font: &FontProto) -> Vec<(f32, String)> {
+    let fallbacks = registered_axis("wght").map_or(&[][..], |a| a.fallbacks);
+    let name = |weight_name: &str| match (font.style(), weight_name) {
+        ("italic", "Regular") => "Italic".to_string(),
+        ("italic", _) => format!("{weight_name} Italic"),
+        _ => weight_name.to_string(),
+    };
+    if let Some((min, max)) = family.axis_range("wght").filter(|_| font.is_variable()) {
+        return fallbacks
+            .iter()
+            .filter(|(_, value)| (min..=max).contains(value))
+            .map(|(weight_name, value)| (*value, name(weight_name)))
+            .collect();
+    }
+    let weight = font.weight() as f32;
+    let nearest = fallbacks
         .iter()
-        .reduce(|acc, e| if score(acc) >= score(e) { acc } else { e })
+        .min_by(|(_, a), (_, b)| (a - weight).abs().total_cmp(&(b - weight).abs()))
+        .map_or("Regular", |(weight_name, _)| weight_name);
+    vec![(weight, name(nearest))]
+}
+
+/// One difference between two versions of a family, from [`family_diff`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum FamilyChange {
+    /// The family `name` changed.
+    NameChanged { old: String, new: String },
+    /// The `designer` credit changed.
+    DesignerChanged { old: String, new: String },
+    /// The `license` changed.
+    LicenseChanged { old: String, new: String },
+    /// The `category` list changed.
+    CategoryChanged { old: Vec<String>, new: Vec<String> },
+    /// A font with this filename was added.
+    FontAdded(String),
+    /// A font with this filename was removed.
+    FontRemoved(String),
+    /// A font's `weight` changed.
+    WeightChanged {
+        filename: String,
+        old: i32,
+        new: i32,
+    },
+    /// A font's `style` changed.
+    StyleChanged {
+        filename: String,
+        old: String,
+        new: String,
+    },
+    /// A subset was added.
+    SubsetAdded(String),
+    /// A subset was removed.
+    SubsetRemoved(String),
+    /// An axis with this tag was added.
+    AxisAdded(String),
+    /// An axis with this tag was removed.
+    AxisRemoved(String),
+    /// An axis's `(min, max)` range changed.
+    AxisChanged {
+        tag: String,
+        old: (f32, f32),
+        new: (f32, f32),
+    },
+}
+
+/// List the differences between two versions of a family.
+///
+/// Fonts are matched by filename and axes by tag, so reordering them isn't
+/// a change. Changes are reported family fields first, then fonts, subsets
+/// and axes.
+pub fn family_diff(old: &FamilyProto, new: &FamilyProto) -> Vec<FamilyChange> {
+    let mut changes = Vec::new();
+    if old.name() != new.name() {
+        changes.push(FamilyChange::NameChanged {
+            old: old.name().to_string(),
+            new: new.name().to_string(),
+        });
+    }
+    if old.designer() != new.designer() {
+        changes.push(FamilyChange::DesignerChanged {
+            old: old.designer().to_string(),
+            new: new.designer().to_string(),
+        });
+    }
+    if old.license() != new.license() {
+        changes.push(FamilyChange::LicenseChanged {
+            old: old.license().to_string(),
+            new: new.license().to_string(),
+        });
+    }
+    if old.category != new.category {
+        changes.push(FamilyChange::CategoryChanged {
+            old: old.category.clone(),
+            new: new.category.clone(),
+        });
+    }
+
+    let new_fonts: HashMap<&str, &FontProto> =
+        new.fonts.iter().map(|f| (f.filename(), f)).collect();
+    for old_font in old.fonts.iter() {
+        let filename = old_font.filename();
+        let Some(new_font) = new_fonts.get(filename) else {
+            changes.push(FamilyChange::FontRemoved(filename.to_string()));
+            continue;
+        };
+        if old_font.weight() != new_font.weight() {
+            changes.push(FamilyChange::WeightChanged {
+                filename: filename.to_string(),
+                old: old_font.weight(),
+                new: new_font.weight(),
+            });
+        }
+        if old_font.style() != new_font.style() {
+            changes.push(FamilyChange::StyleChanged {
+                filename: filename.to_string(),
+                old: old_font.style().to_string(),
+                new: new_font.style().to_string(),
+            });
+        }
+    }
+    changes.extend(
+        new.fonts
+            .iter()
+            .filter(|f| !old.fonts.iter().any(|o| o.filename() == f.filename()))
+            .map(|f| FamilyChange::FontAdded(f.filename().to_string())),
+    );
+
+    changes.extend(
+        old.subsets
+            .iter()
+            .filter(|s| !new.subsets.contains(s))
+            .map(|s| FamilyChange::SubsetRemoved(s.clone())),
+    );
+    changes.extend(
+        new.subsets
+            .iter()
+            .filter(|s| !old.subsets.contains(s))
+            .map(|s| FamilyChange::SubsetAdded(s.clone())),
+    );
+
+    for axis in old.axes.iter() {
+        let tag = axis.tag();
+        match new.axis_range(tag) {
+            None => changes.push(FamilyChange::AxisRemoved(tag.to_string())),
+            Some(range) if range != (axis.min_value(), axis.max_value()) => {
+                changes.push(FamilyChange::AxisChanged {
+                    tag: tag.to_string(),
+                    old: (axis.min_value(), axis.max_value()),
+                    new: range,
+                })
+            }
+            Some(_) => {}
+        }
+    }
+    changes.extend(
+        new.axes
+            .iter()
+            .filter(|a| old.axis_range(a.tag()).is_none())
+            .map(|a| FamilyChange::AxisAdded(a.tag().to_string())),
+    );
+    changes
+}
+
+/// How a family's variability differs between two versions, from
+/// [`GoogleFonts::variability_changes`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VariabilityChange {
+    /// The family had only static fonts and now has a variable font.
+    StaticToVariable,
+    /// The family had a variable font and now has only static fonts.
+    VariableToStatic,
+    /// The family is variable in both versions but its declared axes differ.
+    AxesChanged,
+}
+
+/// Whether a family gained a variable font, judged by
+/// [`FamilyExt::is_variable`].
+pub fn became_variable(old: &FamilyProto, new: &FamilyProto) -> bool {
+    !old.is_variable() && new.is_variable()
+}
+
+fn variability_change(old: &FamilyProto, new: &FamilyProto) -> Option<VariabilityChange> {
+    match (old.is_variable(), new.is_variable()) {
+        (false, true) => Some(VariabilityChange::StaticToVariable),
+        (true, false) => Some(VariabilityChange::VariableToStatic),
+        (true, true) => family_diff(old, new)
+            .iter()
+            .any(|c| {
+                matches!(
+                    c,
+                    FamilyChange::AxisAdded(_)
+                        | FamilyChange::AxisRemoved(_)
+                        | FamilyChange::AxisChanged { .. }
+                )
+            })
+            .then_some(VariabilityChange::AxesChanged),
+        (false, false) => None,
+    }
+}
+
+/// Orderings for [`GoogleFonts::families_sorted_by`].
+///
+/// `METADATA.pb` has no popularity field, so popularity can't be offered.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SortKey {
+    /// By display name (or name, if there's no display name), ignoring case
+    Name,
+    /// By `date_added`, newest first; families without a valid date sort last
+    DateAdded,
 }
 
 /// Font style preference for font selection (normal or italic)
@@ -89,461 +712,6645 @@ impl FontStyle {
     }
 }
 
+/// How a family's [`exemplar`] depends on its variable font.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ExemplarStability {
+    /// The exemplar is the same with or without variable fonts.
+    Stable,
+    /// Without variable fonts a different static font would be chosen.
+    ChangesWithoutVariable,
+    /// The family has only variable fonts, so there is nothing to fall back to.
+    NoStaticFallback,
+}
+
+/// A family's license, as encoded by the repo directory it lives in.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum License {
+    /// `ofl/`: SIL Open Font License
+    Ofl,
+    /// `apache/`: Apache License 2.0
+    Apache,
+    /// `ufl/`: Ubuntu Font License
+    Ufl,
+    /// The family isn't under any of the license directories.
+    Unknown,
+}
+
+/// How [`GoogleFonts::primary_language_with_reason`] chose a language.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum LanguageReason {
+    /// The family's declared `primary_language`.
+    DeclaredLanguage,
+    /// The most populous known language in the family's `languages` list.
+    LanguagesFallback,
+    /// The most populous language using the family's `primary_script`.
+    ScriptFallback,
+    /// Nothing was declared, or the declared script matches no languages, so
+    /// `en_Latn` was used.
+    DefaultLatin,
+    /// The declared `primary_language` is unknown; the language came from a
+    /// fallback instead.
+    InvalidDeclaredLanguage,
+}
+
 /// Select the best matching font from a family given style and weight preferences.
 pub fn select_font(
     family: &FamilyProto,
     preferred_style: FontStyle,
     preferred_weight: i32,
 ) -> Option<&FontProto> {
-    let score =
-        |font: &FontProto| -> i32 { exemplar_score(font, preferred_style, preferred_weight) };
+    select_font_with_width(family, preferred_style, preferred_weight, 100)
+}
+
+/// Select the best matching font, naming the weight rather than giving a
+/// number, e.g. "bold" or "Extra Light".
+///
+/// Returns `None` if `weight_name` isn't a known weight name (see
+/// [`weight_name_to_number`]), so callers can report it rather than silently
+/// falling back to regular.
+pub fn select_font_by_weight_name<'a>(
+    family: &'a FamilyProto,
+    preferred_style: FontStyle,
+    weight_name: &str,
+) -> Option<&'a FontProto> {
+    select_font(family, preferred_style, weight_name_to_number(weight_name)?)
+}
+
+/// Map a CSS/OpenType weight name to its numeric weight, e.g. "SemiBold" to 600.
+///
+/// Matching ignores case, spaces, hyphens and underscores, and accepts the
+/// common aliases ("normal" and "regular" are 400, "heavy" is 900). Returns
+/// `None` for unknown names.
+pub fn weight_name_to_number(name: &str) -> Option<i32> {
+    let name: String = name
+        .chars()
+        .filter(|c| !matches!(c, ' ' | '-' | '_'))
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+    let weight = match name.as_str() {
+        "thin" | "hairline" => 100,
+        "extralight" | "ultralight" => 200,
+        "light" => 300,
+        "regular" | "normal" => 400,
+        "medium" => 500,
+        "semibold" | "demibold" => 600,
+        "bold" => 700,
+        "extrabold" | "ultrabold" => 800,
+        "black" | "heavy" => 900,
+        _ => return None,
+    };
+    Some(weight)
+}
+
+/// Select the heaviest font of a style whose weight doesn't exceed `max_weight`.
+///
+/// A static font counts at its declared weight. A variable font counts at
+/// `max_weight` clamped to the family's declared `wght` range, and is ruled
+/// out if that range starts above `max_weight`. Ties prefer a static font,
+/// then the smaller filename. Returns `None` if no font of the style is light
+/// enough.
+pub fn select_font_at_most(
+    family: &FamilyProto,
+    preferred_style: FontStyle,
+    max_weight: i32,
+) -> Option<&FontProto> {
+    let wght = family.axis_range("wght");
     family
         .fonts
         .iter()
-        .reduce(|acc, e| if score(acc) >= score(e) { acc } else { e })
-}
-
-fn iter_families(
-    root: &Path,
-    filter: Option<&Regex>,
-) -> impl Iterator<Item = (PathBuf, Result<FamilyProto, ParseError>)> {
-    WalkDir::new(root)
-        .into_iter()
-        .filter_map(|d| d.ok())
-        .filter(|d| d.file_name() == "METADATA.pb")
-        .filter(move |d| {
-            filter
-                .map(|r| r.find(&d.path().to_string_lossy()).is_some())
-                .unwrap_or(true)
+        .filter(|f| f.style() == preferred_style.style())
+        .filter_map(|f| {
+            let weight = match wght {
+                Some((min, max)) if f.is_variable() => {
+                    (min <= max_weight as f32).then(|| max_weight.min(max as i32))?
+                }
+                _ => Some(f.weight()).filter(|w| *w <= max_weight)?,
+            };
+            Some((f, weight))
         })
-        .map(|d| {
-            (
-                d.path().to_path_buf(),
-                read_family(&fs::read_to_string(d.path()).expect("To read files!")),
-            )
+        .max_by(|(a, wa), (b, wb)| {
+            wa.cmp(wb)
+                .then_with(|| filename_axes(b).len().cmp(&filename_axes(a).len()))
+                .then_with(|| b.filename().cmp(a.filename()))
         })
+        .map(|(f, _)| f)
 }
 
-/// Iterate over all known languages.
-pub fn iter_languages(_root: &Path) -> impl Iterator<Item = Result<LanguageProto, ParseError>> {
-    LANGUAGES.values().map(|l| Ok(*l.clone()))
+/// Select the best matching font given style, weight and width preferences.
+///
+/// `preferred_width` is a `wdth` axis value (100 is normal, 75 condensed).
+/// Static fonts' widths are inferred from width names in their filenames;
+/// variable fonts with a `wdth` axis cover every width and score neutrally.
+/// Returns `None` only for a family with no fonts.
+pub fn select_font_with_width(
+    family: &FamilyProto,
+    preferred_style: FontStyle,
+    preferred_weight: i32,
+    preferred_width: i32,
+) -> Option<&FontProto> {
+    best_scoring(family.fonts.iter(), |font| {
+        exemplar_score(font, preferred_style, preferred_weight, preferred_width)
+    })
 }
 
-/// Read tag entries from the tags/all directory.
-pub fn read_tags(root: &Path) -> Result<Vec<Tagging>, Error> {
-    let mut tag_dir = root.to_path_buf();
-    tag_dir.push("tags/all");
-    let mut tags = Vec::new();
-    for entry in fs::read_dir(&tag_dir).expect("To read tag dir") {
-        let entry = entry.expect("To access tag dir entries");
-        if entry
-            .path()
-            .extension()
-            .expect("To have extensions")
-            .to_str()
-            .expect("utf-8")
-            != "csv"
-        {
-            continue;
-        }
-        let fd = File::open(entry.path())?;
-        let rdr = BufReader::new(fd);
-        tags.extend(
-            rdr.lines()
-                .map(|s| s.expect("Valid tag lines"))
-                .map(|s| Tagging::from_str(&s).expect("Valid tag lines")),
-        );
-    }
-    Ok(tags)
+/// Whether a family is monospaced.
+///
+/// Google Fonts records this either as the `MONOSPACE` category or as a
+/// `monospace` classification; both are checked, case-insensitively.
+pub fn is_monospace(family: &FamilyProto) -> bool {
+    family
+        .category
+        .iter()
+        .chain(family.classifications.iter())
+        .any(|c| c.eq_ignore_ascii_case("monospace"))
 }
 
-/// Read tag metadata from tags/tags_metadata.csv
-pub fn read_tag_metadata(root: &Path) -> Result<Vec<TagMetadata>, Error> {
-    let mut tag_metadata_file = root.to_path_buf();
-    tag_metadata_file.push("tags/tags_metadata.csv");
-    let mut metadata = Vec::new();
-
-    let fd = File::open(&tag_metadata_file)?;
-    let rdr = BufReader::new(fd);
-    metadata.extend(
-        rdr.lines()
-            .map(|s| s.expect("Valid tag lines"))
-            .map(|s| TagMetadata::from_str(&s).expect("Valid tag metadata lines")),
-    );
+/// Build a CSS `font-family` stack for a family, e.g. `"Roboto", sans-serif`.
+///
+/// The generic fallback comes from the family's first category:
+///
+/// | Category      | Generic      |
+/// |---------------|--------------|
+/// | `SERIF`       | `serif`      |
+/// | `SANS_SERIF`  | `sans-serif` |
+/// | `MONOSPACE`   | `monospace`  |
+/// | `HANDWRITING` | `cursive`    |
+/// | `DISPLAY`     | `sans-serif` |
+///
+/// Monospaced families (see [`is_monospace`]) always get `monospace`, and
+/// anything else falls back to `sans-serif`, matching fonts.google.com.
+pub fn css_font_stack(family: &FamilyProto) -> String {
+    let generic = if is_monospace(family) {
+        "monospace"
+    } else {
+        match family.category.first().map(String::as_str) {
+            Some("SERIF") => "serif",
+            Some("HANDWRITING") => "cursive",
+            _ => "sans-serif",
+        }
+    };
+    format!("\"{}\", {generic}", family.name())
+}
 
-    Ok(metadata)
+/// Generate CSS `@font-face` rules for every font in a family.
+///
+/// Each font's `src` is `base_url` joined with its filename. Variable fonts
+/// (those with axes in their filename) get a `font-weight` range taken from
+/// the family's declared `wght` axis and a `tech(variations)` hint; static
+/// fonts get their single weight. A `unicode-range` covering the family's
+/// subsets is added when every subset (other than `menu`) has a known range;
+/// see [`subset_unicode_range`].
+pub fn css_font_face(family: &FamilyProto, base_url: &str) -> String {
+    let base_url = base_url.trim_end_matches('/');
+    let unicode_range = family
+        .subsets
+        .iter()
+        .filter(|s| *s != "menu")
+        .map(|s| subset_unicode_range(s))
+        .collect::<Option<Vec<_>>>()
+        .filter(|ranges| !ranges.is_empty())
+        .map(|ranges| ranges.join(", "));
+    let mut css = String::new();
+    for font in family.fonts.iter() {
+        let variable = font.is_variable();
+        let weight = match family.axis_range("wght") {
+            Some((min, max)) if variable => format!("{min} {max}"),
+            _ => font.weight().to_string(),
+        };
+        let url = if base_url.is_empty() {
+            font.filename().to_string()
+        } else {
+            format!("{base_url}/{}", font.filename())
+        };
+        let format = match font.filename().rsplit_once('.').map(|(_, ext)| ext) {
+            Some("otf") => "opentype",
+            Some("woff") => "woff",
+            Some("woff2") => "woff2",
+            _ => "truetype",
+        };
+        let tech = if variable { " tech(variations)" } else { "" };
+        css.push_str("@font-face {\n");
+        css.push_str(&format!("  font-family: \"{}\";\n", family.name()));
+        css.push_str(&format!("  font-style: {};\n", font.style()));
+        css.push_str(&format!("  font-weight: {weight};\n"));
+        css.push_str(&format!(
+            "  src: url(\"{url}\") format(\"{format}\"){tech};\n"
+        ));
+        if let Some(range) = &unicode_range {
+            css.push_str(&format!("  unicode-range: {range};\n"));
+        }
+        css.push_str("}\n");
+    }
+    css
 }
 
-fn csv_values(s: &str) -> Vec<&str> {
-    let mut s = s;
-    let mut values = Vec::new();
-    while !s.is_empty() {
-        s = s.trim();
-        let mut end_idx = None;
-        if let Some(s) = s.strip_prefix('"') {
-            end_idx = Some(s.find('"').expect("Close quote"));
-        }
-        end_idx = s[end_idx.unwrap_or_default()..]
-            .find(',')
-            .map(|v| v + end_idx.unwrap_or_default());
-        if let Some(end_idx) = end_idx {
-            let (value, rest) = s.split_at(end_idx);
-            values.push(value.trim());
-            s = &rest[1..];
+/// Build the fonts.googleapis.com CSS2 API URL for a family.
+///
+/// The query lists every style and weight the family offers, e.g.
+/// `family=Lora:ital,wght@0,400;0,700;1,400`. A style with a variable font
+/// whose filename encodes `wght` contributes the declared `wght` range
+/// instead (`0,100..900`). The `ital` axis is only named if the family has
+/// italics.
+pub fn css2_url(family: &FamilyProto) -> String {
+    let has_italic = family.fonts.iter().any(|f| f.style() == "italic");
+    let mut tuples: Vec<(i32, i32, String)> = Vec::new();
+    for (ital, style) in [(0, FontStyle::Normal), (1, FontStyle::Italic)] {
+        let fonts: Vec<_> = family
+            .fonts
+            .iter()
+            .filter(|f| f.style() == style.style())
+            .collect();
+        let variable_range = fonts
+            .iter()
+            .any(|f| filename_axes(f).contains(&"wght"))
+            .then(|| family.axis_range("wght"))
+            .flatten();
+        if let Some((min, max)) = variable_range {
+            tuples.push((ital, min as i32, format!("{min}..{max}")));
         } else {
-            values.push(s);
-            s = "";
+            tuples.extend(
+                fonts
+                    .iter()
+                    .map(|f| (ital, f.weight(), f.weight().to_string())),
+            );
         }
     }
-    values
+    tuples.sort();
+    tuples.dedup();
+    let (axes, values): (&str, Vec<String>) = if has_italic {
+        (
+            "ital,wght",
+            tuples
+                .into_iter()
+                .map(|(ital, _, weight)| format!("{ital},{weight}"))
+                .collect(),
+        )
+    } else {
+        (
+            "wght",
+            tuples.into_iter().map(|(_, _, weight)| weight).collect(),
+        )
+    };
+    format!(
+        "https://fonts.googleapis.com/css2?family={}:{axes}@{}",
+        family.name().replace(' ', "+"),
+        values.join(";")
+    )
 }
 
-/// A tag entry for a family
+/// Whether a font's filename follows the Google Fonts naming convention.
 ///
-/// A tagging is an association of a family (and optionally a specific
-/// designspace location within that family) with a tag and a numeric value for that tag.
-#[derive(Clone, Debug)]
-pub struct Tagging {
-    /// Font family name
-    pub family: String,
-    /// Optional designspace location within the family
-    ///
-    /// This is given in the form used in the fonts web API; for example, `ital,wght@1,700`
-    /// refers to the italic style at weight 700.
-    pub loc: String,
-    /// Tag name
-    pub tag: String,
-    /// Tag value
-    pub value: f32,
+/// The filename must start with the family name with spaces removed, then
+/// either an axis list (`RobotoSlab[wght].ttf`) or a `-Style` suffix
+/// (`RobotoSlab-Bold.ttf`).
+pub fn filename_matches_convention(family: &FamilyProto, font: &FontProto) -> bool {
+    let stem: String = family.name().split_whitespace().collect();
+    font.filename()
+        .strip_prefix(stem.as_str())
+        .is_some_and(|rest| rest.starts_with('[') || rest.starts_with('-'))
 }
 
-impl FromStr for Tagging {
-    type Err = Error;
+/// Report the fonts in a family whose filenames break the naming convention.
+///
+/// See [`filename_matches_convention`].
+pub fn filename_convention_offenders(family: &FamilyProto) -> Vec<&FontProto> {
+    family
+        .fonts
+        .iter()
+        .filter(|font| !filename_matches_convention(family, font))
+        .collect()
+}
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let values = csv_values(s);
-        let (family, loc, tag, value) = match values[..] {
-            [family, tag, value] => (family, "", tag, value),
-            [family, loc, tag, value] => (family, loc, tag, value),
-            _ => return Err(Error::new(ErrorKind::InvalidData, "Unparseable tag")),
-        };
-        Ok(Tagging {
-            family: family.to_string(),
-            loc: loc.to_string(),
-            tag: tag.to_string(),
-            value: f32::from_str(value)
-                .map_err(|_| Error::new(ErrorKind::InvalidData, "Invalid tag value"))?,
-        })
-    }
+/// Weights accepted for static fonts: multiples of 100 from 100 to 900, plus
+/// 250 and 350 which some families use for intermediate cuts.
+const STANDARD_WEIGHTS: [i32; 11] = [100, 200, 250, 300, 350, 400, 500, 600, 700, 800, 900];
+
+/// Report static fonts whose declared weight isn't a standard value.
+///
+/// Accepted weights are 100 to 900 in steps of 100, plus 250 and 350.
+/// Variable fonts are skipped since their `weight` only records the default
+/// instance.
+pub fn nonstandard_weights(family: &FamilyProto) -> Vec<(&FontProto, i32)> {
+    family
+        .fonts
+        .iter()
+        .filter(|f| !f.is_variable())
+        .filter(|f| !STANDARD_WEIGHTS.contains(&f.weight()))
+        .map(|f| (f, f.weight()))
+        .collect()
 }
 
-/// Metadata for a tag
-#[derive(Clone, Debug)]
-pub struct TagMetadata {
-    /// Tag name (e.g. "/Quality/Drawing")
-    pub tag: String,
-    /// Minimum tag value
-    pub min_value: f32,
-    /// Maximum tag value
-    pub max_value: f32,
-    /// User friendly name for the tag (e.g. "drawing quality")
-    pub prompt_name: String,
+/// Whether a family offers exactly the requested weight and style.
+///
+/// True when a static font of that style has exactly that weight, or a
+/// variable font of that style exists and the family's declared `wght` axis
+/// covers the weight. Unlike [`select_font`] this never settles for the
+/// nearest match.
+pub fn has_exact_style(family: &FamilyProto, weight: i32, style: FontStyle) -> bool {
+    family.has_font(style, weight)
 }
 
-impl FromStr for TagMetadata {
-    type Err = Error;
+/// Lay out the weight × style grid a family offers, for a type-tester matrix.
+///
+/// Rows are every weight the family offers: its static weights plus the
+/// standard weights (multiples of 100) inside a variable font's declared
+/// `wght` range. Columns are the styles present. Each cell holds the static
+/// font with exactly that weight and style, or the variable font of that
+/// style that covers the weight, or `None` if the combination isn't
+/// available. Cells are ordered by weight, then normal before italic.
+pub fn style_grid(family: &FamilyProto) -> Vec<(i32, FontStyle, Option<&FontProto>)> {
+    let wght_range = family.axis_range("wght");
+    let mut weights: Vec<i32> = Vec::new();
+    for font in family.fonts.iter() {
+        match wght_range {
+            Some((min, max)) if font.is_variable() => weights.extend(
+                (1..=9)
+                    .map(|w| w * 100)
+                    .filter(|w| (min..=max).contains(&(*w as f32))),
+            ),
+            _ => weights.push(font.weight()),
+        }
+    }
+    weights.sort();
+    weights.dedup();
+    let styles: Vec<FontStyle> = [FontStyle::Normal, FontStyle::Italic]
+        .into_iter()
+        .filter(|s| family.fonts.iter().any(|f| f.style() == s.style()))
+        .collect();
+    let mut grid = Vec::new();
+    for weight in weights {
+        for style in styles.iter().copied() {
+            let candidates = || {
+                family
+                    .fonts
+                    .iter()
+                    .filter(move |f| f.style() == style.style())
+            };
+            let font = candidates()
+                .find(|f| !f.is_variable() && f.weight() == weight)
+                .or_else(|| {
+                    candidates().find(|f| {
+                        f.is_variable()
+                            && wght_range
+                                .is_some_and(|(min, max)| (min..=max).contains(&(weight as f32)))
+                    })
+                });
+            grid.push((weight, style, font));
+        }
+    }
+    grid
+}
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let values = csv_values(s);
-        let [tag, min, max, prompt_name] = values[..] else {
-            return Err(Error::new(
-                ErrorKind::InvalidData,
-                "Unparseable tag metadata, wrong number of values",
-            ));
+/// The directory name used for a designer under `catalog/designers`.
+///
+/// This lowercases the name and drops everything but letters and digits, so
+/// "Christian Robertson" becomes `christianrobertson`.
+fn designer_slug(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// The axis tags encoded in a font's filename, e.g. `["wdth", "wght"]` for
+/// `Roboto[wdth,wght].ttf`. Static fonts yield no tags.
+///
+/// This reflects the filename only, which should but may not agree with the
+/// family's declared `axes`.
+pub fn filename_axes(font: &FontProto) -> Vec<&str> {
+    let filename = font.filename();
+    let Some((_, rest)) = filename.split_once('[') else {
+        return Vec::new();
+    };
+    let Some((axes, _)) = rest.split_once(']') else {
+        return Vec::new();
+    };
+    axes.split(',')
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .collect()
+}
+
+/// Count the axis tags in a font's filename: `[wdth,wght]` is 2, static is 0.
+///
+/// A cheap alternative to inspecting the family's `axes`; see
+/// [`filename_axes`] for the caveat that filenames and declarations can
+/// disagree.
+pub fn filename_axis_count(font: &FontProto) -> usize {
+    filename_axes(font).len()
+}
+
+/// Conventional width names and their `wdth` axis values (OS/2 width
+/// classes), from the registry's `wdth` fallbacks.
+fn width_names() -> &'static [(&'static str, f32)] {
+    registered_axis("wdth").map_or(&[][..], |a| a.fallbacks)
+}
+
+/// The width of a static font, inferred from a width name in its filename.
+fn static_font_width(font: &FontProto) -> f32 {
+    let filename = font.filename();
+    // The longest match wins so "SemiCondensed" isn't read as "Condensed"
+    width_names()
+        .iter()
+        .filter(|(name, _)| filename.contains(name))
+        .max_by_key(|(name, _)| name.len())
+        .map(|(_, width)| *width)
+        .unwrap_or(100.0)
+}
+
+/// Name the width a family would render at for a requested `wdth` value.
+///
+/// For a family with a `wdth` axis the value is clamped to the declared range;
+/// otherwise the nearest static width cut is used, where static widths are
+/// inferred from width names in the filenames. The result is the nearest
+/// conventional name, from "UltraCondensed" (50) to "UltraExpanded" (200).
+/// Returns `None` if the family has no width variation.
+pub fn nearest_width_name(family: &FamilyProto, width: f32) -> Option<&str> {
+    let distance = |w: f32| (w - width).abs();
+    let resolved = if let Some((min, max)) = family.axis_range("wdth") {
+        width.clamp(min, max)
+    } else {
+        let widths: Vec<f32> = family.fonts.iter().map(static_font_width).collect();
+        if widths.iter().all(|w| *w == 100.0) {
+            return None;
+        }
+        widths
+            .into_iter()
+            .min_by(|a, b| distance(*a).total_cmp(&distance(*b)))?
+    };
+    width_names()
+        .iter()
+        .min_by(|(_, a), (_, b)| (a - resolved).abs().total_cmp(&(b - resolved).abs()))
+        .map(|(name, _)| *name)
+}
+
+/// Return a family's declared `primary_language` verbatim, even if invalid.
+///
+/// [`GoogleFonts::primary_language`] substitutes a fallback when the declared
+/// id is unknown; this is the raw value, for tooling that reports bad ids.
+pub fn declared_primary_language(family: &FamilyProto) -> Option<&str> {
+    family.primary_language.as_deref()
+}
+
+/// The expected location of a font binary, beside its `METADATA.pb`.
+fn font_path(metadata: &Path, font: &FontProto) -> PathBuf {
+    metadata.with_file_name(font.filename())
+}
+
+/// Lowercase a family name and drop whitespace, for fuzzy comparison.
+fn normalize_family_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Score how alike two normalized family names are, from 0.0 to 1.0.
+fn family_name_similarity(a: &str, b: &str) -> f64 {
+    if a == b {
+        return 1.0;
+    }
+    let (a, b): (Vec<char>, Vec<char>) = (a.chars().collect(), b.chars().collect());
+    let longest = a.len().max(b.len());
+    if longest == 0 {
+        return 0.0;
+    }
+    // Levenshtein distance, one row at a time
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    let edit_score = 1.0 - row[b.len()] as f64 / longest as f64;
+    let (shorter, longer) = if a.len() <= b.len() {
+        (&a, &b)
+    } else {
+        (&b, &a)
+    };
+    let substring_score =
+        if !shorter.is_empty() && longer.windows(shorter.len()).any(|w| w == &shorter[..]) {
+            shorter.len() as f64 / longer.len() as f64
+        } else {
+            0.0
         };
-        Ok(TagMetadata {
-            tag: tag.into(),
-            min_value: f32::from_str(min)
-                .map_err(|_| Error::new(ErrorKind::InvalidData, "Invalid min value"))?,
-            max_value: f32::from_str(max)
-                .map_err(|_| Error::new(ErrorKind::InvalidData, "Invalid min value"))?,
-            prompt_name: prompt_name.into(),
+    // Anything short of an exact match stays below 1.0
+    edit_score.max(substring_score).min(0.99)
+}
+
+/// Whether `path` resolves to somewhere under `canonical_root`, so a
+/// symlink can't lead a walk out of the repository.
+fn is_within(path: &Path, canonical_root: Option<&Path>) -> bool {
+    match (canonical_root, fs::canonicalize(path)) {
+        (Some(root), Ok(path)) => path.starts_with(root),
+        _ => false,
+    }
+}
+
+fn iter_metadata_paths(root: &Path, filter: Option<&Regex>) -> impl Iterator<Item = PathBuf> {
+    let canonical_root = fs::canonicalize(root).ok();
+    WalkDir::new(root)
+        .into_iter()
+        .filter_map(|d| d.ok())
+        .filter(|d| d.file_name() == "METADATA.pb")
+        .filter(move |d| is_within(d.path(), canonical_root.as_deref()))
+        .filter(move |d| {
+            filter
+                .map(|r| r.find(&d.path().to_string_lossy()).is_some())
+                .unwrap_or(true)
         })
+        .map(|d| d.into_path())
+}
+
+/// The newest modification time among the files and directories that
+/// determine what families and tags a repository holds.
+fn newest_mtime(root: &Path, filter: Option<&Regex>) -> Option<SystemTime> {
+    let mtime = |p: &Path| fs::metadata(p).and_then(|m| m.modified()).ok();
+    let metadata = iter_metadata_paths(root, filter).flat_map(|path| {
+        path.ancestors()
+            .take(3)
+            .filter_map(mtime)
+            .collect::<Vec<_>>()
+    });
+    let tags = WalkDir::new(root.join("tags"))
+        .into_iter()
+        .filter_map(|d| d.ok())
+        .filter_map(|d| d.metadata().ok()?.modified().ok());
+    metadata.chain(tags).max()
+}
+
+/// Errors reading Google Fonts metadata.
+#[derive(Debug)]
+pub enum GfError {
+    /// A file couldn't be read.
+    Io {
+        /// The file
+        path: PathBuf,
+        /// Why it couldn't be read
+        error: Error,
+    },
+    /// A file was read but isn't valid protobuf text format.
+    Parse {
+        /// The file, e.g. a family's `METADATA.pb`
+        path: PathBuf,
+        /// Why it couldn't be parsed
+        error: ParseError,
+    },
+    /// A line of a CSV file couldn't be parsed.
+    Csv {
+        /// The CSV file
+        path: PathBuf,
+        /// 1-based line number
+        line: usize,
+        /// What was wrong with the line
+        message: String,
+    },
+    /// A required file or directory doesn't exist.
+    NotFound(PathBuf),
+}
+
+/// The error for a family's `METADATA.pb` that couldn't be loaded.
+///
+/// Families only ever fail with [`GfError::Io`] or [`GfError::Parse`], and
+/// both name the `METADATA.pb` at fault.
+pub type FamilyError = GfError;
+
+impl GfError {
+    /// Convert an I/O error on `path`, reporting a missing file as `NotFound`.
+    fn from_io(path: &Path, e: Error) -> Self {
+        if e.kind() == ErrorKind::NotFound {
+            GfError::NotFound(path.to_path_buf())
+        } else {
+            GfError::Io {
+                path: path.to_path_buf(),
+                error: e,
+            }
+        }
     }
 }
 
-/// A view into the Google Fonts library.
+impl Display for GfError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GfError::Io { path, error } => {
+                write!(f, "unable to read {}: {error}", path.display())
+            }
+            GfError::Parse { path, error } => {
+                write!(f, "unable to parse {}: {error}", path.display())
+            }
+            GfError::Csv {
+                path,
+                line,
+                message,
+            } => write!(f, "{}:{line}: {message}", path.display()),
+            GfError::NotFound(path) => write!(f, "{} not found", path.display()),
+        }
+    }
+}
+
+impl std::error::Error for GfError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            GfError::Io { error, .. } => Some(error),
+            GfError::Parse { error, .. } => Some(error),
+            GfError::Csv { .. } | GfError::NotFound(_) => None,
+        }
+    }
+}
+
+/// A `METADATA.pb` found in the repository and the result of loading it.
+#[derive(Debug)]
+pub struct FamilyEntry {
+    /// Path to the `METADATA.pb`
+    pub path: PathBuf,
+    /// The parsed family, or why it couldn't be read or parsed
+    pub family: Result<FamilyProto, GfError>,
+}
+
+impl FamilyEntry {
+    /// Path to the `METADATA.pb`.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The family, if it was read and parsed successfully.
+    pub fn family(&self) -> Option<&FamilyProto> {
+        self.family.as_ref().ok()
+    }
+}
+
+pub(crate) fn load_family(path: PathBuf) -> FamilyEntry {
+    let family = fs::read_to_string(&path)
+        .map_err(|e| GfError::from_io(&path, e))
+        .and_then(|s| {
+            read_family(&s).map_err(|error| GfError::Parse {
+                path: path.clone(),
+                error,
+            })
+        });
+    FamilyEntry { path, family }
+}
+
+fn iter_families(root: &Path, filter: Option<&Regex>) -> impl Iterator<Item = FamilyEntry> {
+    iter_metadata_paths(root, filter).map(load_family)
+}
+
+/// Walk `catalog/designers` and parse every designer's `info.pb`.
 ///
-/// This struct holds a path to a local checkout of the Google Fonts repo and
-/// provides cached, read-only accessors for families, tags and language
-/// metadata. All accessors return borrowed references where possible so callers
-/// should hold the `GoogleFonts` value for as long as they need the returned
-/// references.
-pub struct GoogleFonts {
-    repo_dir: PathBuf,
-    family_filter: Option<Regex>,
-    families: OnceCell<Vec<(PathBuf, Result<FamilyProto, ParseError>)>>,
-    family_by_font_file: OnceCell<HashMap<String, usize>>,
-    tags: OnceCell<Result<Vec<Tagging>, Error>>,
-    tag_metadata: OnceCell<Result<Vec<TagMetadata>, Error>>,
+/// Files that can't be read, or that resolve outside `root`, are skipped.
+fn iter_designers(
+    root: &Path,
+) -> impl Iterator<Item = (PathBuf, Result<DesignerInfoProto, ParseError>)> {
+    let canonical_root = fs::canonicalize(root).ok();
+    WalkDir::new(root.join("catalog/designers"))
+        .into_iter()
+        .filter_map(|d| d.ok())
+        .filter(|d| d.file_name() == "info.pb")
+        .filter(move |d| is_within(d.path(), canonical_root.as_deref()))
+        .filter_map(|d| {
+            let content = fs::read_to_string(d.path()).ok()?;
+            Some((
+                d.into_path(),
+                protobuf::text_format::parse_from_str(&content),
+            ))
+        })
 }
 
-impl GoogleFonts {
-    /// Create a new `GoogleFonts` view.
-    ///
-    /// `p` should be the path to the root of a local Google Fonts repository
-    /// checkout (the directory containing `METADATA.pb` files and the
-    /// `tags/` directory). `family_filter`, if present, is a regular
-    /// expression used to filter which families are exposed by the
-    /// `families()` iterator.
-    ///
-    /// This constructor does not perform I/O; metadata is read lazily when
-    /// the corresponding accessor is called.
-    pub fn new(p: PathBuf, family_filter: Option<Regex>) -> Self {
-        Self {
-            repo_dir: p,
-            family_filter,
-            families: OnceCell::new(),
-            family_by_font_file: OnceCell::new(),
-            tags: OnceCell::new(),
-            tag_metadata: OnceCell::new(),
+/// Read and parse every family serially, in walk order.
+#[cfg(not(feature = "rayon"))]
+fn load_all_families(root: &Path, filter: Option<&Regex>) -> Vec<FamilyEntry> {
+    iter_families(root, filter).collect()
+}
+
+/// Read and parse every family in parallel on rayon's global pool.
+///
+/// The walk itself is serial; parsing is parallel and results keep walk order.
+#[cfg(feature = "rayon")]
+fn load_all_families(root: &Path, filter: Option<&Regex>) -> Vec<FamilyEntry> {
+    use rayon::prelude::*;
+    let paths: Vec<_> = iter_metadata_paths(root, filter).collect();
+    paths.into_par_iter().map(load_family).collect()
+}
+
+/// Read and parse families using up to `concurrency` threads.
+///
+/// Paths are split into contiguous chunks, one per thread, and the results
+/// are concatenated in chunk order so the output matches the serial order.
+fn load_families_concurrently(
+    root: &Path,
+    filter: Option<&Regex>,
+    concurrency: usize,
+) -> Vec<FamilyEntry> {
+    let paths: Vec<_> = iter_metadata_paths(root, filter).collect();
+    if paths.is_empty() {
+        return Vec::new();
+    }
+    let chunk_size = paths.len().div_ceil(concurrency);
+    thread::scope(|scope| {
+        let handles: Vec<_> = paths
+            .chunks(chunk_size)
+            .map(|chunk| {
+                scope.spawn(move || chunk.iter().cloned().map(load_family).collect::<Vec<_>>())
+            })
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|h| h.join().expect("Family loader thread panicked"))
+            .collect()
+    })
+}
+
+/// Iterate over all known languages.
+pub fn iter_languages(_root: &Path) -> impl Iterator<Item = Result<LanguageProto, ParseError>> {
+    LANGUAGES.values().map(|l| Ok(*l.clone()))
+}
+
+/// Iterate over all known regions.
+pub fn iter_regions(_root: &Path) -> impl Iterator<Item = Result<RegionProto, ParseError>> {
+    REGIONS.values().map(|r| Ok(*r.clone()))
+}
+
+/// Iterate over all known scripts.
+pub fn iter_scripts(_root: &Path) -> impl Iterator<Item = Result<ScriptProto, ParseError>> {
+    SCRIPTS.values().map(|s| Ok(*s.clone()))
+}
+
+/// Pick the best sample string for a language.
+///
+/// Fields of the language's `SampleTextProto` are tried in order, from the
+/// fullest masthead through the tester and poster strings down to the
+/// specimen sizes; the first non-empty one wins.
+pub fn language_sample_text(lang: &LanguageProto) -> Option<&str> {
+    sample_text_fields(lang).first().copied()
+}
+
+/// Pangrams to fall back on for languages without sample text, by script.
+static SCRIPT_PANGRAMS: [(&str, &str); 5] = [
+    ("Latn", "The quick brown fox jumps over the lazy dog."),
+    (
+        "Cyrl",
+        "Съешь же ещё этих мягких французских булок, да выпей чаю.",
+    ),
+    ("Grek", "Ξεσκεπάζω την ψυχοφθόρα βδελυγμία."),
+    ("Hebr", "דג סקרן שט בים מאוכזב ולפתע מצא לו חברה"),
+    ("Jpan", "いろはにほへと ちりぬるを わかよたれそ つねならむ"),
+];
+
+/// Text to render for a language, whatever it provides.
+///
+/// This is [`language_sample_text`] if the language has sample text.
+/// Otherwise it is a built-in pangram for the language's script (Latin,
+/// Cyrillic, Greek, Hebrew and Japanese are known), and failing that the
+/// Latin pangram, so the result is never empty.
+pub fn sample_or_pangram(lang: &LanguageProto) -> String {
+    if let Some(sample) = language_sample_text(lang) {
+        return sample.to_string();
+    }
+    let pangram = |script: &str| {
+        SCRIPT_PANGRAMS
+            .iter()
+            .find(|(s, _)| *s == script)
+            .map(|(_, pangram)| *pangram)
+    };
+    pangram(lang.script())
+        .or_else(|| pangram("Latn"))
+        .expect("Latin has a pangram")
+        .to_string()
+}
+
+/// The non-empty sample strings of a language, in order of preference.
+fn sample_text_fields(lang: &LanguageProto) -> Vec<&str> {
+    let Some(sample) = lang.sample_text.as_ref() else {
+        return Vec::new();
+    };
+    [
+        &sample.masthead_full,
+        &sample.masthead_partial,
+        &sample.styles,
+        &sample.tester,
+        &sample.poster_lg,
+        &sample.poster_md,
+        &sample.poster_sm,
+        &sample.specimen_48,
+        &sample.specimen_36,
+        &sample.specimen_32,
+        &sample.specimen_21,
+        &sample.specimen_16,
+    ]
+    .into_iter()
+    .filter_map(|s| s.as_deref())
+    .filter(|s| !s.is_empty())
+    .collect()
+}
+
+/// Languages implied by a family's metadata.
+///
+/// This is the union of the family's declared `languages` and every known
+/// language written in a script covered by one of its declared subsets.
+fn implied_languages(family: &FamilyProto) -> Vec<&'static LanguageProto> {
+    let scripts: Vec<&str> = family
+        .subsets
+        .iter()
+        .filter_map(|s| subset_script(s))
+        .collect();
+    let mut langs: Vec<&'static LanguageProto> = family
+        .languages
+        .iter()
+        .filter_map(|id| LANGUAGES.get(id.as_str()))
+        .map(|l| &**l)
+        .collect();
+    langs.extend(
+        LANGUAGES
+            .values()
+            .filter(|l| l.script.is_some() && scripts.contains(&l.script()))
+            .map(|l| &**l),
+    );
+    langs
+}
+
+/// Known languages written in `script`, most populous first.
+fn languages_using_script(script: &str) -> Vec<&'static LanguageProto> {
+    let mut languages: Vec<_> = LANGUAGES
+        .values()
+        .filter(|l| l.script.is_some() && l.script() == script)
+        .map(|l| &**l)
+        .collect();
+    languages.sort_by_key(|l| std::cmp::Reverse(l.population()));
+    languages
+}
+
+/// Canonicalise a user-supplied language identifier to the `lang_Script`
+/// form used by `LANGUAGES`: hyphens become underscores, the language code is
+/// lowercased and a four-letter script is titlecased.
+fn normalize_language_id(lang_id: &str) -> String {
+    lang_id
+        .trim()
+        .split(['_', '-'])
+        .enumerate()
+        .map(|(i, part)| match i {
+            0 => part.to_ascii_lowercase(),
+            _ if part.len() == 4 && part.is_ascii() => {
+                let mut part = part.to_ascii_lowercase();
+                part[..1].make_ascii_uppercase();
+                part
+            }
+            _ => part.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+/// The most populous known language written in `script`.
+fn most_populous_language(script: &str) -> Option<&'static LanguageProto> {
+    languages_using_script(script).first().copied()
+}
+
+/// Split a language's base exemplar characters into individual characters.
+///
+/// The base exemplars are space-separated, and multi-character clusters are
+/// wrapped in `{...}`; clusters contribute each of their characters. A
+/// language without exemplar characters yields nothing.
+pub fn language_base_chars(lang: &LanguageProto) -> Vec<char> {
+    let Some(base) = lang.exemplar_chars.as_ref().and_then(|e| e.base.as_deref()) else {
+        return Vec::new();
+    };
+    base.split_whitespace()
+        .flat_map(|token| {
+            token
+                .strip_prefix('{')
+                .and_then(|t| t.strip_suffix('}'))
+                .unwrap_or(token)
+                .chars()
+        })
+        .collect()
+}
+
+/// Whether the font binary at `path` maps every base exemplar character of
+/// `lang` in its `cmap`.
+///
+/// Requires the `skrifa` feature. Fails if the file can't be read or isn't
+/// a font.
+#[cfg(feature = "skrifa")]
+pub fn font_covers_language(path: &Path, lang: &LanguageProto) -> Result<bool, Error> {
+    use skrifa::{FontRef, MetadataProvider};
+    let data = fs::read(path)?;
+    let font =
+        FontRef::new(&data).map_err(|e| Error::new(ErrorKind::InvalidData, e.to_string()))?;
+    Ok(charmap_covers_language(&font.charmap(), lang))
+}
+
+#[cfg(feature = "skrifa")]
+fn charmap_covers_language(charmap: &skrifa::charmap::Charmap, lang: &LanguageProto) -> bool {
+    language_base_chars(lang)
+        .into_iter()
+        .all(|c| charmap.map(c).is_some())
+}
+
+/// The design-space extent of a single font, as `(tag, min, max)` triples.
+///
+/// Static fonts sit at a single point: `wght` is the declared weight and
+/// `ital` is 1 for italics and 0 otherwise. Variable fonts (bracketed
+/// filenames) additionally span every axis range declared on the family.
+fn font_coordinates(family: &FamilyProto, font: &FontProto) -> Vec<(String, f32, f32)> {
+    let ital = if font.style() == "italic" { 1.0 } else { 0.0 };
+    let weight = font.weight() as f32;
+    let mut coords = vec![
+        ("ital".to_string(), ital, ital),
+        ("wght".to_string(), weight, weight),
+    ];
+    if font.is_variable() {
+        for axis in family.axes.iter() {
+            coords.retain(|(tag, _, _)| tag != axis.tag());
+            coords.push((axis.tag().to_string(), axis.min_value(), axis.max_value()));
+        }
+    }
+    coords
+}
+
+/// Why `axis` at `value` isn't a location in `family`, if it isn't.
+///
+/// See [`GoogleFonts::invalid_tag_locations`] for the rules.
+fn location_problem(family: &FamilyProto, axis: &str, value: f32) -> Option<String> {
+    match (axis, family.axis_range(axis)) {
+        (_, Some((min, max))) if value < min || value > max => {
+            Some(format!("{axis} value {value} outside {min}..{max}"))
+        }
+        (_, Some(_)) => None,
+        ("ital", None) if value == 0.0 => None,
+        ("ital", None) if value == 1.0 => (!family.fonts.iter().any(|f| f.style() == "italic"))
+            .then(|| "ital@1 but family has no italic".to_string()),
+        ("wght", None) if family.fonts.iter().any(|f| f.weight() as f32 == value) => None,
+        _ => Some(format!("axis {axis} not declared by family")),
+    }
+}
+
+/// Read tag entries from the tags/all directory.
+///
+/// Blank lines and `#` comment lines are skipped, as is a header row (e.g.
+/// `Family,Group/Tag,Weight`) on the first line of a file; [`Tagging::from_str`]
+/// itself accepts none of these. A line that fails to parse produces a
+/// [`GfError::Csv`] naming the file and 1-based line number, displayed as
+/// e.g. `tags/all/families.csv:12: Invalid tag value`. A missing directory is
+/// [`GfError::NotFound`].
+pub fn read_tags(root: &Path) -> Result<Vec<Tagging>, GfError> {
+    let mut tag_dir = root.to_path_buf();
+    tag_dir.push("tags/all");
+    read_tag_dir(&tag_dir)
+}
+
+/// Read and merge tag entries from several tag directories.
+///
+/// Each directory is read like `tags/all` (every `.csv` file in it). Entries
+/// are keyed on `(family, loc, tag)`: when a later directory repeats a key
+/// from an earlier one, the later value replaces the earlier entry in place,
+/// so a staging directory can be layered over the baseline. Within a single
+/// directory, duplicates are kept as-is.
+pub fn read_tags_from(dirs: &[&Path]) -> Result<Vec<Tagging>, GfError> {
+    let mut tags: Vec<Tagging> = Vec::new();
+    let mut index: HashMap<(String, String, String), usize> = HashMap::new();
+    for dir in dirs {
+        let mut layer_index = HashMap::new();
+        for tag in read_tag_dir(dir)? {
+            let key = (tag.family.clone(), tag.loc.clone(), tag.tag.clone());
+            if let Some(&i) = index.get(&key) {
+                tags[i] = tag;
+            } else {
+                layer_index.insert(key, tags.len());
+                tags.push(tag);
+            }
+        }
+        index.extend(layer_index);
+    }
+    Ok(tags)
+}
+
+fn read_tag_dir(tag_dir: &Path) -> Result<Vec<Tagging>, GfError> {
+    iter_tag_dir(tag_dir.to_path_buf()).collect()
+}
+
+/// Lazily read tag entries from the tags/all directory.
+///
+/// Files are opened one at a time as the iterator advances, so a caller that
+/// filters taggings never holds the whole corpus in memory. Errors are
+/// yielded as for [`read_tags`]; iteration may continue past them.
+pub fn iter_tags(root: &Path) -> impl Iterator<Item = Result<Tagging, GfError>> {
+    iter_tag_dir(root.join("tags/all"))
+}
+
+fn iter_tag_dir(tag_dir: PathBuf) -> impl Iterator<Item = Result<Tagging, GfError>> {
+    let paths: Box<dyn Iterator<Item = Result<PathBuf, GfError>>> = match fs::read_dir(&tag_dir) {
+        Ok(entries) => Box::new(entries.map(move |e| {
+            e.map(|e| e.path())
+                .map_err(|e| GfError::from_io(&tag_dir, e))
+        })),
+        Err(e) => Box::new(std::iter::once(Err(GfError::from_io(&tag_dir, e)))),
+    };
+    paths.flat_map(|path| -> TagFileIter {
+        match path {
+            Ok(path) if path.extension().is_none_or(|ext| ext != "csv") => {
+                Box::new(std::iter::empty())
+            }
+            Ok(path) => iter_tags_file(path),
+            Err(e) => Box::new(std::iter::once(Err(e))),
         }
+    })
+}
+
+/// Read the tag entries of a single tag CSV file, e.g. one file of
+/// `tags/all`.
+///
+/// Lines are handled exactly as by [`read_tags`], which reads each file of the
+/// directory this way; callers watching individual files can re-read just the
+/// one that changed. A missing file is [`GfError::NotFound`].
+pub fn read_tags_file(path: &Path) -> Result<Vec<Tagging>, GfError> {
+    iter_tags_file(path.to_path_buf()).collect()
+}
+
+type TagFileIter = Box<dyn Iterator<Item = Result<Tagging, GfError>>>;
+
+fn iter_tags_file(path: PathBuf) -> TagFileIter {
+    let file = match File::open(&path) {
+        Ok(file) => file,
+        Err(e) => return Box::new(std::iter::once(Err(GfError::from_io(&path, e)))),
+    };
+    Box::new(
+        BufReader::new(file)
+            .lines()
+            .enumerate()
+            .filter(|(i, line)| {
+                !matches!(line, Ok(l) if {
+                    let l = csv_line(*i, l).trim();
+                    l.is_empty() || l.starts_with('#')
+                })
+            })
+            .filter(|(i, line)| !matches!(line, Ok(l) if *i == 0 && is_tag_header(l)))
+            .map(move |(i, line)| {
+                let line = line.map_err(|e| GfError::from_io(&path, e))?;
+                Tagging::from_str(csv_line(i, &line)).map_err(|e| GfError::Csv {
+                    path: path.clone(),
+                    line: i + 1,
+                    message: e.to_string(),
+                })
+            }),
+    )
+}
+
+/// Read tag metadata from tags/tags_metadata.csv
+///
+/// Entries are returned in file order, which is the curated order in which
+/// tags should be presented.
+pub fn read_tag_metadata(root: &Path) -> Result<Vec<TagMetadata>, GfError> {
+    let mut tag_metadata_file = root.to_path_buf();
+    tag_metadata_file.push("tags/tags_metadata.csv");
+
+    let fd = File::open(&tag_metadata_file).map_err(|e| GfError::from_io(&tag_metadata_file, e))?;
+    let rdr = BufReader::new(fd);
+    rdr.lines()
+        .enumerate()
+        .map(|(i, s)| {
+            let s = s.map_err(|e| GfError::from_io(&tag_metadata_file, e))?;
+            TagMetadata::from_str(csv_line(i, &s)).map_err(|e| GfError::Csv {
+                path: tag_metadata_file.clone(),
+                line: i + 1,
+                message: e.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Whether the first line of a tag file is a header rather than a tagging:
+/// it has a tagging's shape but its value column isn't a number.
+fn is_tag_header(line: &str) -> bool {
+    let values = csv_values(csv_line(0, line));
+    matches!(values.len(), 3 | 4) && values[values.len() - 1].parse::<f32>().is_err()
+}
+
+/// Strip what Windows editors add to a CSV line: a UTF-8 byte order mark on
+/// the first line (`i` is 0-based) and a trailing carriage return.
+fn csv_line(i: usize, line: &str) -> &str {
+    let line = if i == 0 {
+        line.strip_prefix('\u{feff}').unwrap_or(line)
+    } else {
+        line
+    };
+    line.trim_end_matches('\r')
+}
+
+/// Parse a `date_added` value such as "2013-01-01" into a comparable
+/// `(year, month, day)`.
+fn parse_date_added(date: &str) -> Option<(i32, u32, u32)> {
+    let mut parts = date.trim().splitn(3, '-');
+    let year = parts.next()?.parse().ok()?;
+    let month = parts
+        .next()?
+        .parse()
+        .ok()
+        .filter(|m| (1..=12).contains(m))?;
+    let day = parts
+        .next()?
+        .parse()
+        .ok()
+        .filter(|d| (1..=31).contains(d))?;
+    Some((year, month, day))
+}
+
+/// Split a CSV line into trimmed values.
+///
+/// A value wrapped in double quotes may contain commas, and a doubled `""`
+/// inside it stands for a literal quote (RFC 4180). An unterminated quoted
+/// value runs to the end of the line.
+fn csv_values(s: &str) -> Vec<Cow<'_, str>> {
+    let mut values = Vec::new();
+    let mut rest = s.trim();
+    while !rest.is_empty() {
+        if let Some(quoted) = rest.strip_prefix('"') {
+            let mut value = String::new();
+            let mut end = quoted.len();
+            let mut chars = quoted.char_indices().peekable();
+            while let Some((i, c)) = chars.next() {
+                if c != '"' {
+                    value.push(c);
+                } else if chars.next_if(|(_, c)| *c == '"').is_some() {
+                    value.push('"');
+                } else {
+                    end = i + 1;
+                    break;
+                }
+            }
+            values.push(Cow::Owned(value));
+            let after = &quoted[end..];
+            rest = after.find(',').map_or("", |i| after[i + 1..].trim_start());
+        } else if let Some((value, after)) = rest.split_once(',') {
+            values.push(Cow::Borrowed(value.trim()));
+            rest = after.trim_start();
+        } else {
+            values.push(Cow::Borrowed(rest.trim()));
+            rest = "";
+        }
+    }
+    values
+}
+
+/// Quote a CSV value if it contains a comma or a quote, doubling any quotes.
+fn csv_field(s: &str) -> Cow<'_, str> {
+    if s.contains([',', '"']) {
+        Cow::Owned(format!("\"{}\"", s.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(s)
+    }
+}
+
+/// A tag entry for a family
+///
+/// A tagging is an association of a family (and optionally a specific
+/// designspace location within that family) with a tag and a numeric value for that tag.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tagging {
+    /// Font family name
+    pub family: String,
+    /// Optional designspace location within the family
+    ///
+    /// This is given in the form used in the fonts web API; for example, `ital,wght@1,700`
+    /// refers to the italic style at weight 700.
+    pub loc: String,
+    /// Tag name
+    pub tag: String,
+    /// Tag value
+    pub value: f32,
+}
+
+impl FromStr for Tagging {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let values = csv_values(s);
+        let values: Vec<&str> = values.iter().map(|v| v.as_ref()).collect();
+        let (family, loc, tag, value) = match values[..] {
+            [family, tag, value] => (family, "", tag, value),
+            [family, loc, tag, value] => (family, loc, tag, value),
+            _ => return Err(Error::new(ErrorKind::InvalidData, "Unparseable tag")),
+        };
+        Ok(Tagging {
+            family: family.to_string(),
+            loc: loc.to_string(),
+            tag: tag.to_string(),
+            value: f32::from_str(value)
+                .map_err(|_| Error::new(ErrorKind::InvalidData, "Invalid tag value"))?,
+        })
+    }
+}
+
+impl Tagging {
+    /// Parse `loc` into a [`DesignspaceLocation`].
+    pub fn location(&self) -> Result<DesignspaceLocation, Error> {
+        self.loc.parse()
+    }
+}
+
+impl Display for Tagging {
+    /// Write the tagging as a CSV line that `from_str` reads back.
+    ///
+    /// The location column is omitted when `loc` is empty, and quoted when it
+    /// contains commas (e.g. `"ital,wght@1,700"`).
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},", csv_field(&self.family))?;
+        if !self.loc.is_empty() {
+            write!(f, "{},", csv_field(&self.loc))?;
+        }
+        write!(f, "{},{}", csv_field(&self.tag), self.value)
+    }
+}
+
+/// A designspace location within a family, as used in the fonts web API.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DesignspaceLocation {
+    /// Axis tag and user-space value pairs, in the order given
+    pub coordinates: Vec<(String, f32)>,
+}
+
+impl DesignspaceLocation {
+    /// True for the default location, which has no coordinates.
+    pub fn is_default(&self) -> bool {
+        self.coordinates.is_empty()
+    }
+
+    /// Value for the given axis tag, if the location specifies one.
+    pub fn value(&self, tag: &str) -> Option<f32> {
+        self.coordinates
+            .iter()
+            .find(|(axis, _)| axis == tag)
+            .map(|(_, value)| *value)
+    }
+}
+
+impl FromStr for DesignspaceLocation {
+    type Err = Error;
+
+    /// Parse a location such as `ital,wght@1,700`.
+    ///
+    /// Surrounding quotes are ignored. An empty string is the default location
+    /// and yields no coordinates.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let loc = s.trim().trim_matches('"');
+        if loc.is_empty() {
+            return Ok(Self::default());
+        }
+        let (axes, values) = loc
+            .split_once('@')
+            .ok_or_else(|| Error::new(ErrorKind::InvalidData, "Location has no '@'"))?;
+        let axes: Vec<_> = axes.split(',').map(str::trim).collect();
+        let values: Vec<_> = values.split(',').map(str::trim).collect();
+        if axes.len() != values.len() {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "Location has mismatched axes and values",
+            ));
+        }
+        let coordinates = axes
+            .into_iter()
+            .zip(values)
+            .map(|(axis, value)| {
+                f32::from_str(value)
+                    .map(|v| (axis.to_string(), v))
+                    .map_err(|_| Error::new(ErrorKind::InvalidData, "Invalid location value"))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { coordinates })
+    }
+}
+
+/// Metadata for a tag
+#[derive(Clone, Debug, PartialEq)]
+pub struct TagMetadata {
+    /// Tag name (e.g. "/Quality/Drawing")
+    pub tag: String,
+    /// Minimum tag value
+    pub min_value: f32,
+    /// Maximum tag value
+    pub max_value: f32,
+    /// User friendly name for the tag (e.g. "drawing quality")
+    pub prompt_name: String,
+}
+
+impl FromStr for TagMetadata {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let values = csv_values(s);
+        let values: Vec<&str> = values.iter().map(|v| v.as_ref()).collect();
+        let [tag, min, max, prompt_name] = values[..] else {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "Unparseable tag metadata, wrong number of values",
+            ));
+        };
+        Ok(TagMetadata {
+            tag: tag.into(),
+            min_value: f32::from_str(min)
+                .map_err(|_| Error::new(ErrorKind::InvalidData, "Invalid min value"))?,
+            max_value: f32::from_str(max)
+                .map_err(|_| Error::new(ErrorKind::InvalidData, "Invalid min value"))?,
+            prompt_name: prompt_name.into(),
+        })
+    }
+}
+
+impl Display for TagMetadata {
+    /// Write the metadata as a `tags_metadata.csv` line that `from_str` reads back.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{},{},{},{}",
+            csv_field(&self.tag),
+            self.min_value,
+            self.max_value,
+            csv_field(&self.prompt_name)
+        )
+    }
+}
+
+/// Observed values of a tag across all taggings, from [`GoogleFonts::tag_statistics`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct TagStat {
+    /// Tag name (e.g. "/Quality/Drawing")
+    pub tag: String,
+    /// Number of taggings using the tag
+    pub count: usize,
+    /// Smallest value the tag is given
+    pub observed_min: f32,
+    /// Largest value the tag is given
+    pub observed_max: f32,
+    /// Mean of the tag's values
+    pub mean: f32,
+}
+
+/// Coverage differences between two families, from [`GoogleFonts::coverage_delta`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CoverageDelta {
+    /// Subsets declared by the original family but not the replacement
+    pub lost_subsets: Vec<String>,
+    /// Subsets declared by the replacement but not the original family
+    pub gained_subsets: Vec<String>,
+    /// Scripts covered by the original family but not the replacement
+    pub lost_scripts: Vec<String>,
+    /// Scripts covered by the replacement but not the original family
+    pub gained_scripts: Vec<String>,
+}
+
+/// Disagreements between a family's declared subsets and its font's
+/// coverage, from [`GoogleFonts::subset_mismatch`].
+#[cfg(feature = "skrifa")]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SubsetMismatch {
+    /// Subsets the family declares but its font doesn't cover
+    pub uncovered: Vec<String>,
+    /// Subsets the font covers but the family doesn't declare
+    pub undeclared: Vec<String>,
+}
+
+/// Translate a family directory glob into a regex over `METADATA.pb` paths.
+fn glob_family_regex(pattern: &str) -> Regex {
+    let pattern = pattern.trim_matches('/');
+    let mut re = String::from("(^|/)");
+    let mut chars = pattern.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                re.push_str(".*");
+            }
+            '*' => re.push_str("[^/]*"),
+            '?' => re.push_str("[^/]"),
+            '[' if chars.clone().any(|c| c == ']') => {
+                re.push('[');
+                if chars.next_if(|c| *c == '!' || *c == '^').is_some() {
+                    re.push('^');
+                }
+                for c in chars.by_ref() {
+                    if c == ']' {
+                        break;
+                    }
+                    if c == '\\' || c == '[' {
+                        re.push('\\');
+                    }
+                    re.push(c);
+                }
+                re.push(']');
+            }
+            _ => re.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    re.push_str("/METADATA\\.pb$");
+    Regex::new(&re).expect("Valid re")
+}
+
+/// Summary counts for a repository, from [`GoogleFonts::stats`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RepoStats {
+    /// `METADATA.pb` files found
+    pub families: usize,
+    /// Families that loaded successfully
+    pub parsed: usize,
+    /// Families that couldn't be read or parsed
+    pub failed: usize,
+    /// Parsed families with at least one variable font
+    pub variable: usize,
+    /// Font files declared across parsed families
+    pub fonts: usize,
+    /// Distinct `primary_script` values across parsed families
+    pub scripts: usize,
+    /// Tag entries, or zero if tags can't be read
+    pub taggings: usize,
+}
+
+/// Which families loaded and which didn't, from [`GoogleFonts::parse_report`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ParseReport {
+    /// Families that loaded successfully
+    pub parsed: usize,
+    /// Families that couldn't be read or parsed
+    pub failed: usize,
+    /// Each failing `METADATA.pb` and its error message, in `families()` order
+    pub failures: Vec<(PathBuf, String)>,
+}
+
+/// Configures and constructs a [`GoogleFonts`] view.
+///
+/// Obtain one with [`GoogleFonts::builder`], chain setters, then call
+/// [`GoogleFontsBuilder::build`].
+#[derive(Default)]
+pub struct GoogleFontsBuilder {
+    repo_dir: PathBuf,
+    family_filter: Option<Regex>,
+    concurrency: usize,
+    binary_search_roots: Vec<PathBuf>,
+}
+
+impl GoogleFontsBuilder {
+    /// Set the root of the local Google Fonts repository checkout.
+    pub fn repo(mut self, p: PathBuf) -> Self {
+        self.repo_dir = p;
+        self
+    }
+
+    /// Only expose families whose `METADATA.pb` path matches `filter`.
+    pub fn family_filter(mut self, filter: Regex) -> Self {
+        self.family_filter = Some(filter);
+        self
+    }
+
+    /// Only expose families whose directory matches the glob `pattern`.
+    ///
+    /// The pattern is matched against the trailing components of the family
+    /// directory, so `ofl/roboto*` selects `ofl/roboto` and `ofl/robotoslab`
+    /// wherever the repository lives. `*` and `?` don't cross `/`, `**` does,
+    /// and `[...]` matches a character class. This replaces any regex filter.
+    pub fn glob_filter(self, pattern: &str) -> Self {
+        self.family_filter(glob_family_regex(pattern))
+    }
+
+    /// Search these directories for font binaries missing beside their
+    /// `METADATA.pb`; see [`GoogleFonts::find_font_binary`].
+    pub fn binary_search_roots(mut self, roots: Vec<PathBuf>) -> Self {
+        self.binary_search_roots = roots;
+        self
+    }
+
+    /// Read and parse `METADATA.pb` files across `n` threads.
+    ///
+    /// The default (and any value of 0 or 1) loads families serially, or on
+    /// rayon's thread pool when the `rayon` feature is enabled. Results are
+    /// collected in the same order either way.
+    pub fn concurrency(mut self, n: usize) -> Self {
+        self.concurrency = n;
+        self
+    }
+
+    /// Build the `GoogleFonts` view. No I/O is performed.
+    pub fn build(self) -> GoogleFonts {
+        GoogleFonts {
+            repo_dir: self.repo_dir,
+            family_filter: self.family_filter,
+            concurrency: self.concurrency.max(1),
+            binary_search_roots: self.binary_search_roots,
+            families: OnceCell::new(),
+            family_by_font_file: OnceCell::new(),
+            family_by_font_file_ci: OnceCell::new(),
+            family_by_name: OnceCell::new(),
+            taggings_by_tag: OnceCell::new(),
+            taggings_by_family: OnceCell::new(),
+            designers: OnceCell::new(),
+            tags: OnceCell::new(),
+            tag_metadata: OnceCell::new(),
+            tag_metadata_by_tag: OnceCell::new(),
+            covered_languages: OnceCell::new(),
+            loaded_at: OnceCell::new(),
+        }
+    }
+}
+
+/// A view into the Google Fonts library.
+///
+/// This struct holds a path to a local checkout of the Google Fonts repo and
+/// provides cached, read-only accessors for families, tags and language
+/// metadata. All accessors return borrowed references where possible so callers
+/// should hold the `GoogleFonts` value for as long as they need the returned
+/// references.
+pub struct GoogleFonts {
+    repo_dir: PathBuf,
+    family_filter: Option<Regex>,
+    concurrency: usize,
+    binary_search_roots: Vec<PathBuf>,
+    families: OnceCell<Vec<FamilyEntry>>,
+    family_by_font_file: OnceCell<HashMap<String, usize>>,
+    family_by_font_file_ci: OnceCell<HashMap<String, usize>>,
+    family_by_name: OnceCell<HashMap<String, usize>>,
+    taggings_by_tag: OnceCell<HashMap<String, Vec<usize>>>,
+    taggings_by_family: OnceCell<HashMap<String, Vec<usize>>>,
+    designers: OnceCell<Vec<(PathBuf, Result<DesignerInfoProto, ParseError>)>>,
+    tags: OnceCell<Result<Vec<Tagging>, GfError>>,
+    tag_metadata: OnceCell<Result<Vec<TagMetadata>, GfError>>,
+    tag_metadata_by_tag: OnceCell<HashMap<String, usize>>,
+    covered_languages: OnceCell<Vec<&'static LanguageProto>>,
+    loaded_at: OnceCell<SystemTime>,
+}
+
+impl GoogleFonts {
+    /// Create a new `GoogleFonts` view.
+    ///
+    /// `p` should be the path to the root of a local Google Fonts repository
+    /// checkout (the directory containing `METADATA.pb` files and the
+    /// `tags/` directory). `family_filter`, if present, is a regular
+    /// expression used to filter which families are exposed by the
+    /// `families()` iterator.
+    ///
+    /// This constructor does not perform I/O; metadata is read lazily when
+    /// the corresponding accessor is called.
+    pub fn new(p: PathBuf, family_filter: Option<Regex>) -> Self {
+        GoogleFontsBuilder {
+            repo_dir: p,
+            family_filter,
+            ..Default::default()
+        }
+        .build()
+    }
+
+    /// Create a `GoogleFonts` view over families held in memory, for tests.
+    ///
+    /// Each family is paired with the `METADATA.pb` path it should appear to
+    /// have been read from; nothing is read from disk. As with
+    /// [`families`](Self::families), they're kept sorted by path. Lookups by font, name
+    /// or language work against these families, while anything that needs
+    /// files (font binaries, tags, designers) finds nothing. [`reload`](Self::reload)
+    /// discards the families.
+    pub fn from_families(families: Vec<(PathBuf, FamilyProto)>) -> Self {
+        let gf = GoogleFontsBuilder::default().build();
+        let mut families: Vec<_> = families
+            .into_iter()
+            .map(|(path, family)| FamilyEntry {
+                path,
+                family: Ok(family),
+            })
+            .collect();
+        families.sort_by(|a, b| a.path.cmp(&b.path));
+        let _ = gf.families.set(families);
+        gf
+    }
+
+    /// Create a `GoogleFonts` view whose families are filtered by a glob.
+    ///
+    /// See [`GoogleFontsBuilder::glob_filter`] for how `pattern` is matched.
+    pub fn with_glob_filter(p: PathBuf, pattern: &str) -> Self {
+        Self::builder().repo(p).glob_filter(pattern).build()
+    }
+
+    /// Create a view of the fonts repository containing `start`.
+    ///
+    /// Walks up from `start` (inclusive) to the first directory that has a
+    /// `tags/` directory and a `METADATA.pb` one or two levels below it (as in
+    /// `ofl/roboto/METADATA.pb`). Returns `None` if no ancestor qualifies.
+    pub fn discover(start: &Path, family_filter: Option<Regex>) -> Option<Self> {
+        let root = start.ancestors().find(|dir| {
+            dir.join("tags").is_dir()
+                && WalkDir::new(dir)
+                    .min_depth(2)
+                    .max_depth(3)
+                    .into_iter()
+                    .filter_map(|d| d.ok())
+                    .any(|d| d.file_name() == "METADATA.pb")
+        })?;
+        Some(Self::new(root.to_path_buf(), family_filter))
+    }
+
+    /// Start configuring a `GoogleFonts` view with a [`GoogleFontsBuilder`].
+    pub fn builder() -> GoogleFontsBuilder {
+        GoogleFontsBuilder::default()
+    }
+
+    /// Drop everything cached so the next accessor re-reads from disk.
+    ///
+    /// The repository path, family filter, concurrency and binary search
+    /// roots are kept.
+    pub fn reload(&mut self) {
+        *self = GoogleFontsBuilder {
+            repo_dir: std::mem::take(&mut self.repo_dir),
+            family_filter: self.family_filter.take(),
+            concurrency: self.concurrency,
+            binary_search_roots: std::mem::take(&mut self.binary_search_roots),
+        }
+        .build();
+    }
+
+    /// [`GoogleFonts::reload`] if the repository changed since it was read.
+    ///
+    /// A change is any `METADATA.pb`, family directory, license directory or
+    /// file under `tags/` modified after families or tags were first read;
+    /// adding or removing a family updates its parent directory. Returns
+    /// whether a reload happened. Nothing happens if nothing was read yet.
+    pub fn reload_if_changed(&mut self) -> bool {
+        let Some(loaded_at) = self.loaded_at.get().copied() else {
+            return false;
+        };
+        let changed = newest_mtime(&self.repo_dir, self.family_filter.as_ref())
+            .is_some_and(|newest| newest > loaded_at);
+        if changed {
+            self.reload();
+        }
+        changed
+    }
+
+    /// Populate every lazily built cache and index up front.
+    ///
+    /// Accessors normally read and index on first use; call this during a
+    /// warmup phase so later queries don't pay that cost. Errors reading
+    /// tags or tag metadata are cached as usual and surface from their
+    /// accessors. With the `serde` feature, [`GoogleFonts::dump_index`] and
+    /// [`GoogleFonts::load_index`] carry what was read over to a later run.
+    pub fn build_all_indexes(&self) {
+        self.families();
+        self.family_by_font_file();
+        self.family_by_font_file_ci();
+        self.family_by_name_index();
+        self.designers();
+        let _ = self.tags();
+        self.taggings_by_tag();
+        self.taggings_by_family();
+        let _ = self.tag_metadata();
+        self.tag_metadata_by_tag();
+        self.covered_languages();
+    }
+
+    /// Return the parsed tag entries for the repository.
+    ///
+    /// On first call this will read and parse the CSV files from the repo's
+    /// `tags/all` directory. Returns `Ok(&[Tag])` when parsing succeeded, or
+    /// `Err(&GfError)` if an I/O or parse error occurred. The returned slice is
+    /// borrowed from internal storage and remains valid for the lifetime of
+    /// `self`.
+    pub fn tags(&self) -> Result<&[Tagging], &GfError> {
+        self.tags
+            .get_or_init(|| {
+                self.loaded_at.get_or_init(SystemTime::now);
+                read_tags(&self.repo_dir)
+            })
+            .as_ref()
+            .map(|tags| tags.as_slice())
+    }
+    /// Return tag metadata (min/max and prompt names) for tags defined in
+    /// the repository.
+    ///
+    /// This reads `tags/tags_metadata.csv` on first access and returns a
+    /// borrowed slice on success. Errors are returned as `Err(&GfError)`.
+    pub fn tag_metadata(&self) -> Result<&[TagMetadata], &GfError> {
+        self.tag_metadata
+            .get_or_init(|| read_tag_metadata(&self.repo_dir))
+            .as_ref()
+            .map(|metadata| metadata.as_slice())
+    }
+    /// Return tag metadata in the order it appears in `tags_metadata.csv`.
+    ///
+    /// That order is the curated display order for tag sliders. Returns an
+    /// empty slice if the metadata can't be read; use
+    /// [`GoogleFonts::tag_metadata`] to see the error.
+    pub fn tag_metadata_ordered(&self) -> &[TagMetadata] {
+        self.tag_metadata().unwrap_or_default()
+    }
+    /// Look up the metadata for a tag, e.g. "/Quality/Drawing".
+    ///
+    /// Returns `None` if the tag has no metadata or tag metadata can't be
+    /// read.
+    pub fn tag_metadata_for(&self, tag: &str) -> Option<&TagMetadata> {
+        let i = *self.tag_metadata_by_tag().get(tag)?;
+        self.tag_metadata().ok().map(|m| &m[i])
+    }
+
+    fn tag_metadata_by_tag(&self) -> &HashMap<String, usize> {
+        self.tag_metadata_by_tag.get_or_init(|| {
+            self.tag_metadata()
+                .map(|metadata| {
+                    metadata
+                        .iter()
+                        .enumerate()
+                        .map(|(i, m)| (m.tag.clone(), i))
+                        .collect()
+                })
+                .unwrap_or_default()
+        })
+    }
+
+    /// Return the display position of a tag within `tags_metadata.csv`.
+    ///
+    /// A tag listed more than once is at the position of the entry
+    /// [`GoogleFonts::tag_metadata_for`] returns.
+    pub fn tag_display_index(&self, tag: &str) -> Option<usize> {
+        self.tag_metadata_by_tag().get(tag).copied()
+    }
+    /// Return a list of discovered families and their parsed metadata.
+    ///
+    /// Each [`FamilyEntry`] holds the path to a family's `METADATA.pb` and
+    /// the parsed `FamilyProto`, or a [`GfError`] if the metadata could not be
+    /// read or parsed. Families are
+    /// discovered lazily by scanning the repository and applying the
+    /// `family_filter` provided at construction (if any). A `METADATA.pb`
+    /// that is a symlink resolving outside the repository is ignored.
+    ///
+    /// Entries are sorted by `METADATA.pb` path, so the order doesn't depend
+    /// on how the platform's filesystem happens to walk the repository; use
+    /// [`iter_families_lazy`](Self::iter_families_lazy) for walk order. The
+    /// returned slice is borrowed from internal storage and stays valid
+    /// for the lifetime of `self`.
+    pub fn families(&self) -> &[FamilyEntry] {
+        self.families
+            .get_or_init(|| {
+                self.loaded_at.get_or_init(SystemTime::now);
+                let filter = self.family_filter.as_ref();
+                let mut families = if self.concurrency > 1 {
+                    load_families_concurrently(&self.repo_dir, filter, self.concurrency)
+                } else {
+                    load_all_families(&self.repo_dir, filter)
+                };
+                families.sort_by(|a, b| a.path.cmp(&b.path));
+                families
+            })
+            .as_slice()
+    }
+    /// Iterate over the families that parsed, with their `METADATA.pb` paths.
+    pub fn ok_families(&self) -> impl Iterator<Item = (&Path, &FamilyProto)> {
+        self.families()
+            .iter()
+            .filter_map(|e| e.family().map(|f| (e.path(), f)))
+    }
+
+    /// Iterate over the `METADATA.pb` files that couldn't be loaded, and why.
+    pub fn parse_errors(&self) -> impl Iterator<Item = (&Path, &GfError)> {
+        self.families()
+            .iter()
+            .filter_map(|e| e.family.as_ref().err().map(|err| (e.path(), err)))
+    }
+
+    /// Summarise which families loaded, for CI.
+    ///
+    /// Failures carry the formatted [`GfError`]. With the `serde` feature,
+    /// [`ParseReport::to_json`] gives a machine-readable form.
+    pub fn parse_report(&self) -> ParseReport {
+        let failures: Vec<_> = self
+            .parse_errors()
+            .map(|(path, e)| (path.to_path_buf(), e.to_string()))
+            .collect();
+        ParseReport {
+            parsed: self.families().len() - failures.len(),
+            failed: failures.len(),
+            failures,
+        }
+    }
+
+    /// Return the distinct `primary_script` values declared across families,
+    /// sorted.
+    ///
+    /// Families without a `primary_script` are skipped.
+    pub fn scripts(&self) -> Vec<&str> {
+        self.ok_families()
+            .filter(|(_, f)| f.has_primary_script())
+            .map(|(_, f)| f.primary_script())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    }
+
+    /// Return the families that support a language, e.g. `hi_Deva`.
+    ///
+    /// A family supports a language if any of these hold:
+    /// 1. its `languages` field lists the id,
+    /// 2. its `primary_language` is the id, or
+    /// 3. its `primary_script` is the script the language is written in.
+    ///
+    /// `languages` is often left empty, so the last rule matters; declared
+    /// subsets are not considered. The script rule needs the language to be
+    /// known. Families that failed to parse are skipped.
+    pub fn families_supporting_language(&self, lang_id: &str) -> Vec<&FamilyProto> {
+        let script = self
+            .language(lang_id)
+            .filter(|l| l.script.is_some())
+            .map(|l| l.script());
+        self.ok_families()
+            .map(|(_, f)| f)
+            .filter(|f| {
+                f.languages.iter().any(|l| l == lang_id)
+                    || (f.has_primary_language() && f.primary_language() == lang_id)
+                    || (f.has_primary_script() && Some(f.primary_script()) == script)
+            })
+            .collect()
+    }
+
+    /// Return the families whose `primary_script` is `script`, e.g. "Jpan".
+    pub fn families_for_script(&self, script: &str) -> Vec<&FamilyProto> {
+        self.ok_families()
+            .map(|(_, f)| f)
+            .filter(|f| f.has_primary_script() && f.primary_script() == script)
+            .collect()
+    }
+
+    /// Summarize the repository: family, font, script and tag counts.
+    ///
+    /// Families and tags are read (and cached) if they haven't been yet. A
+    /// checkout without `tags/` counts zero taggings rather than failing.
+    pub fn stats(&self) -> RepoStats {
+        RepoStats {
+            families: self.families().len(),
+            parsed: self.ok_families().count(),
+            failed: self.parse_errors().count(),
+            variable: self.ok_families().filter(|(_, f)| f.is_variable()).count(),
+            fonts: self.all_fonts().count(),
+            scripts: self.scripts().len(),
+            taggings: self.tags().map_or(0, |tags| tags.len()),
+        }
+    }
+
+    /// Return successfully parsed families in catalog order for `key`.
+    ///
+    /// Ties, including families without a `date_added`, are broken by name.
+    pub fn families_sorted_by(&self, key: SortKey) -> Vec<&FamilyProto> {
+        let mut families: Vec<_> = self.ok_families().map(|(_, f)| f).collect();
+        let name = |f: &FamilyProto| {
+            if f.has_display_name() {
+                f.display_name().to_lowercase()
+            } else {
+                f.name().to_lowercase()
+            }
+        };
+        match key {
+            SortKey::Name => families.sort_by_cached_key(|f| name(f)),
+            SortKey::DateAdded => families.sort_by_cached_key(|f| {
+                let date = parse_date_added(f.date_added());
+                (date.is_none(), std::cmp::Reverse(date), name(f))
+            }),
+        }
+        families
+    }
+
+    /// Compare this (older) checkout against `other` (newer) for families whose
+    /// variability changed.
+    ///
+    /// Families are matched by name as [`family_by_name`](Self::family_by_name)
+    /// resolves it; those missing from either view are ignored. Results are
+    /// sorted by family name.
+    pub fn variability_changes(&self, other: &GoogleFonts) -> Vec<(&str, VariabilityChange)> {
+        let mut changes: Vec<_> = self
+            .families_by_name()
+            .filter_map(|(name, old)| {
+                let (_, new) = other.family_by_name(name)?;
+                variability_change(old, new).map(|change| (name, change))
+            })
+            .collect();
+        changes.sort_by(|a, b| a.0.cmp(b.0));
+        changes
+    }
+
+    /// Return the successfully parsed families filed under `category`.
+    ///
+    /// Families are matched on [`FamilyExt::categories`] and keep
+    /// `families()` order.
+    pub fn families_in_category(&self, category: Category) -> Vec<&FamilyProto> {
+        self.families()
+            .iter()
+            .filter_map(FamilyEntry::family)
+            .filter(|f| f.categories().contains(&category))
+            .collect()
+    }
+
+    /// Return the successfully parsed families that declare an axis `tag`,
+    /// e.g. `opsz`.
+    ///
+    /// Families are matched on [`FamilyExt::axis`]; tags are case-sensitive,
+    /// as in OpenType, so `SLNT` doesn't match `slnt`. Results keep
+    /// `families()` order.
+    pub fn families_with_axis(&self, tag: &str) -> Vec<&FamilyProto> {
+        self.families()
+            .iter()
+            .filter_map(FamilyEntry::family)
+            .filter(|f| f.axis(tag).is_some())
+            .collect()
+    }
+
+    /// Return the successfully parsed families with `classification`.
+    ///
+    /// Families are matched on [`FamilyExt::classifications_typed`] and keep
+    /// `families()` order.
+    pub fn families_with_classification(
+        &self,
+        classification: Classification,
+    ) -> Vec<&FamilyProto> {
+        self.families()
+            .iter()
+            .filter_map(FamilyEntry::family)
+            .filter(|f| f.classifications_typed().contains(&classification))
+            .collect()
+    }
+
+    /// Iterate over every font of every successfully parsed family.
+    ///
+    /// Each item is the owning family and the font, in `families()` order.
+    pub fn all_fonts(&self) -> impl Iterator<Item = (&FamilyProto, &FontProto)> {
+        self.families()
+            .iter()
+            .filter_map(FamilyEntry::family)
+            .flat_map(|family| family.fonts.iter().map(move |font| (family, font)))
+    }
+
+    /// Walk and parse families on demand, without caching.
+    ///
+    /// Unlike [`GoogleFonts::families`], which reads the whole repository on
+    /// first access, this reads each `METADATA.pb` only as the iterator
+    /// advances, so short-circuiting queries (e.g. `find`) stop early. The
+    /// `family_filter` is applied, and items are yielded in walk order.
+    pub fn iter_families_lazy(&self) -> impl Iterator<Item = FamilyEntry> {
+        iter_families(&self.repo_dir, self.family_filter.as_ref())
+    }
+    /// List every `METADATA.pb` in the repository, without reading them.
+    ///
+    /// The `family_filter` is applied and paths are sorted, the same order
+    /// as [`families`](Self::families). The walk isn't cached, so each
+    /// call sees the repository as it is now.
+    pub fn metadata_paths(&self) -> Vec<PathBuf> {
+        let mut paths: Vec<_> =
+            iter_metadata_paths(&self.repo_dir, self.family_filter.as_ref()).collect();
+        paths.sort();
+        paths
+    }
+
+    /// Return the first successfully parsed family matching `pred`.
+    ///
+    /// If [`families`](Self::families) has already been loaded it is searched;
+    /// otherwise the repository is walked lazily, as by
+    /// [`iter_families_lazy`](Self::iter_families_lazy), and the walk stops at
+    /// the first match. Families that fail to parse are skipped. Because a lazy
+    /// walk doesn't populate the cache, the match is returned owned.
+    pub fn find_family<F: Fn(&FamilyProto) -> bool>(
+        &self,
+        pred: F,
+    ) -> Option<(PathBuf, FamilyProto)> {
+        if let Some(families) = self.families.get() {
+            return families.iter().find_map(|e| {
+                let family = e.family().filter(|f| pred(f))?;
+                Some((e.path.clone(), family.clone()))
+            });
+        }
+        self.iter_families_lazy()
+            .filter_map(|e| e.family.ok().map(|f| (e.path, f)))
+            .find(|(_, family)| pred(family))
+    }
+
+    /// Return the designers described under `catalog/designers`.
+    ///
+    /// Each entry is the path to a designer's `info.pb` and the parsed
+    /// `DesignerInfoProto`, or the `ParseError` if it couldn't be parsed. The
+    /// directory is walked on first access and the result cached.
+    pub fn designers(&self) -> &[(PathBuf, Result<DesignerInfoProto, ParseError>)] {
+        self.designers
+            .get_or_init(|| iter_designers(&self.repo_dir).collect())
+            .as_slice()
+    }
+
+    /// Look up a designer by the name in their `info.pb`, e.g. "Christian Robertson".
+    pub fn designer(&self, name: &str) -> Option<&DesignerInfoProto> {
+        self.designers()
+            .iter()
+            .filter_map(|(_, d)| d.as_ref().ok())
+            .find(|d| d.designer() == name)
+    }
+
+    /// Resolve a family's credited designers to their `info.pb` entries.
+    ///
+    /// Names come from [`FamilyExt::designer_names`], in credit order; names
+    /// with no matching entry in [`designers`](Self::designers) are skipped.
+    pub fn family_designers(&self, family: &FamilyProto) -> Vec<&DesignerInfoProto> {
+        family
+            .designer_names()
+            .iter()
+            .filter_map(|name| self.designer(name))
+            .collect()
+    }
+
+    /// Lookup a language by its identifier.
+    ///
+    /// The `lang_id` should be the language identifier used by the
+    /// `google-fonts-languages` crate (for example "en_Latn"), but hyphens are
+    /// accepted in place of underscores and case is ignored, so "en-latn" finds
+    /// the same language. Use [`language_exact`](Self::language_exact) for a
+    /// strict lookup.
+    pub fn language(&self, lang_id: &str) -> Option<&LanguageProto> {
+        self.language_exact(lang_id)
+            .or_else(|| self.language_exact(&normalize_language_id(lang_id)))
+    }
+
+    /// Lookup a language by its exact identifier, e.g. "en_Latn".
+    ///
+    /// This is a simple passthrough to the bundled `LANGUAGES` map.
+    pub fn language_exact(&self, lang_id: &str) -> Option<&LanguageProto> {
+        LANGUAGES.get(lang_id).map(|l| &**l)
+    }
+
+    /// Lookup a region by its identifier, e.g. "IN".
+    ///
+    /// This is a simple passthrough to the bundled `REGIONS` map.
+    pub fn region(&self, region_id: &str) -> Option<&RegionProto> {
+        REGIONS.get(region_id).map(|r| &**r)
+    }
+
+    /// Lookup a script by its identifier, e.g. "Deva".
+    ///
+    /// This is a simple passthrough to the bundled `SCRIPTS` map.
+    pub fn script(&self, script_id: &str) -> Option<&ScriptProto> {
+        SCRIPTS.get(script_id).map(|s| &**s)
+    }
+
+    /// The most populous language for a bare language code, e.g. "en".
+    ///
+    /// Matches identifiers whose first component is `code` (compared
+    /// case-insensitively), so "sr" finds whichever of `sr_Cyrl` and `sr_Latn`
+    /// has the larger population. Ties go to the lexically smaller identifier.
+    pub fn language_by_prefix(&self, code: &str) -> Option<&LanguageProto> {
+        let code = code.to_ascii_lowercase();
+        LANGUAGES
+            .iter()
+            .filter(|(id, _)| id.split('_').next() == Some(code.as_str()))
+            .min_by(|(a_id, a), (b_id, b)| {
+                b.population()
+                    .cmp(&a.population())
+                    .then_with(|| a_id.cmp(b_id))
+            })
+            .map(|(_, l)| &**l)
+    }
+
+    /// Return sample text for an explicit language, independent of any family.
+    ///
+    /// This is useful for offering a language switcher on a specimen page.
+    /// The richest available `SampleTextProto` field is returned (see
+    /// [`language_sample_text`]). Returns `None` if the language is unknown or
+    /// has no sample text.
+    pub fn sample_text_in(&self, lang_id: &str) -> Option<&str> {
+        self.language(lang_id).and_then(language_sample_text)
+    }
+
+    /// Return the English display name of a language, e.g. "Japanese".
+    pub fn language_name(&self, lang_id: &str) -> Option<&str> {
+        self.language(lang_id).and_then(|l| l.name.as_deref())
+    }
+
+    /// Return the name of a language in that language, e.g. "日本語".
+    pub fn language_autonym(&self, lang_id: &str) -> Option<&str> {
+        self.language(lang_id).and_then(|l| l.autonym.as_deref())
+    }
+
+    /// Return every language supported by at least one family.
+    ///
+    /// A family supports the languages it declares in `languages` plus every
+    /// language written in a script covered by its declared subsets. The
+    /// result is deduplicated and sorted by descending population (ties by
+    /// language id). It is computed once and cached.
+    pub fn covered_languages(&self) -> Vec<&LanguageProto> {
+        self.covered_languages
+            .get_or_init(|| {
+                let mut by_id: HashMap<&str, &'static LanguageProto> = HashMap::new();
+                for family in self.families().iter().filter_map(FamilyEntry::family) {
+                    for lang in implied_languages(family) {
+                        by_id.insert(lang.id(), lang);
+                    }
+                }
+                let mut langs: Vec<_> = by_id.into_values().collect();
+                langs.sort_by(|a, b| {
+                    b.population()
+                        .cmp(&a.population())
+                        .then_with(|| a.id().cmp(b.id()))
+                });
+                langs
+            })
+            .clone()
+    }
+
+    /// Report families whose declared primary language and primary script disagree.
+    ///
+    /// Only families declaring both `primary_language` and `primary_script`
+    /// are considered. A conflict is reported when the declared language is
+    /// known and its script differs from the declared primary script. Each
+    /// entry is `(family, primary_language, primary_script)`.
+    pub fn primary_language_script_conflicts(&self) -> Vec<(&FamilyProto, &str, &str)> {
+        self.families()
+            .iter()
+            .filter_map(FamilyEntry::family)
+            .filter(|f| f.has_primary_language() && f.has_primary_script())
+            .filter(|f| {
+                self.language(f.primary_language())
+                    .is_some_and(|l| l.script.is_some() && l.script() != f.primary_script())
+            })
+            .map(|f| (f, f.primary_language(), f.primary_script()))
+            .collect()
+    }
+
+    /// Union of base exemplar characters across a family's declared subsets.
+    ///
+    /// For each declared subset that maps to a script, the most populous
+    /// language written in that script is taken as representative and its base
+    /// exemplar characters are added. Characters are deduplicated and kept in
+    /// first-seen order, following the order of the family's `subsets`.
+    pub fn combined_exemplar_chars(&self, family: &FamilyProto) -> Vec<char> {
+        let mut seen = HashSet::new();
+        family
+            .subsets
+            .iter()
+            .filter_map(|s| subset_script(s))
+            .filter_map(most_populous_language)
+            .flat_map(language_base_chars)
+            .filter(|c| seen.insert(*c))
+            .collect()
+    }
+
+    /// Return the Reserved Font Name declared for a family, if any.
+    ///
+    /// `METADATA.pb` has no field for this, so the information is read from
+    /// the license file (`OFL.txt`, falling back to `LICENSE.txt`) that sits
+    /// beside the family's `METADATA.pb`. The first quoted name in a
+    /// "Reserved Font Name" clause is returned. Returns `None` if the family
+    /// can't be located, has no readable license file, or declares no RFN.
+    pub fn reserved_font_name(&self, family: &FamilyProto) -> Option<String> {
+        let family_dir = self.metadata_path(family)?.parent()?;
+        let license = ["OFL.txt", "LICENSE.txt"]
+            .iter()
+            .find_map(|name| fs::read_to_string(family_dir.join(name)).ok())?;
+        let re = Regex::new(r#"Reserved\s+Font\s+Names?\s*["“']([^"”']+)["”']"#).expect("Valid re");
+        re.captures(&license).map(|c| c[1].trim().to_string())
+    }
+
+    /// Find fonts across the library that can be rendered at the given coordinates.
+    ///
+    /// `coords` is a list of `(axis tag, value)` pairs. A font matches if,
+    /// for every requested axis, the value lies within `tolerance` of the
+    /// font's extent on that axis (a point for static fonts, the declared
+    /// range for variable fonts). Static fonts only know about `wght` and
+    /// `ital`, so requests for other axes only match variable fonts.
+    ///
+    /// Results follow the order of `families()` and then of each family's
+    /// `fonts`, so output is deterministic.
+    pub fn instances_matching(
+        &self,
+        coords: &[(String, f32)],
+        tolerance: f32,
+    ) -> Vec<(&FamilyProto, &FontProto)> {
+        self.families()
+            .iter()
+            .filter_map(FamilyEntry::family)
+            .flat_map(|family| family.fonts.iter().map(move |font| (family, font)))
+            .filter(|(family, font)| {
+                let extent = font_coordinates(family, font);
+                coords.iter().all(|(tag, value)| {
+                    extent.iter().any(|(t, min, max)| {
+                        t == tag && *value >= min - tolerance && *value <= max + tolerance
+                    })
+                })
+            })
+            .collect()
+    }
+
+    /// Count how many families each language is the primary language for.
+    ///
+    /// This runs [`GoogleFonts::primary_language`] over every successfully
+    /// parsed family and tallies the results, sorted by descending count
+    /// (ties broken by language id).
+    pub fn primary_language_distribution(&self) -> Vec<(&LanguageProto, usize)> {
+        let mut counts: HashMap<&str, (&LanguageProto, usize)> = HashMap::new();
+        for family in self.families().iter().filter_map(FamilyEntry::family) {
+            let lang = self.primary_language(family);
+            counts.entry(lang.id()).or_insert((lang, 0)).1 += 1;
+        }
+        let mut counts: Vec<_> = counts.into_values().collect();
+        counts.sort_by(|(a, a_count), (b, b_count)| {
+            b_count.cmp(a_count).then_with(|| a.id().cmp(b.id()))
+        });
+        counts
+    }
+
+    /// Audit the [`exemplar`] heuristic across every parsed family.
+    ///
+    /// A family is flagged when its exemplar is italic even though a
+    /// normal-style font exists, or when another font of the exemplar's style
+    /// is strictly closer to weight 400. Each issue is reported as the family
+    /// and a short reason.
+    pub fn exemplar_sanity_issues(&self) -> Vec<(&FamilyProto, &str)> {
+        let mut issues = Vec::new();
+        for family in self.families().iter().filter_map(FamilyEntry::family) {
+            let Some(chosen) = exemplar(family) else {
+                continue;
+            };
+            if chosen.style() == "italic" && family.fonts.iter().any(|f| f.style() == "normal") {
+                issues.push((family, "exemplar is italic but a normal font exists"));
+            }
+            let distance = |f: &FontProto| (f.weight() - 400).abs();
+            if family
+                .fonts
+                .iter()
+                .any(|f| f.style() == chosen.style() && distance(f) < distance(chosen))
+            {
+                issues.push((family, "a font closer to weight 400 exists"));
+            }
+        }
+        issues
+    }
+
+    /// Return the last-modified time of a family's `METADATA.pb`.
+    ///
+    /// This is a cheap change signal for incremental builds. Returns `None`
+    /// if the family can't be located or its file can't be stat'd.
+    pub fn family_mtime(&self, family: &FamilyProto) -> Option<SystemTime> {
+        fs::metadata(self.metadata_path(family)?)
+            .and_then(|m| m.modified())
+            .ok()
+    }
+
+    /// Report families whose sample text would render in an undeclared script.
+    ///
+    /// The sample language is resolved with [`GoogleFonts::primary_language`];
+    /// a mismatch is reported when that language's script isn't covered by
+    /// any of the family's declared subsets (see [`subset_script`]). Each
+    /// entry is `(family, language id, language script)`.
+    pub fn sample_script_mismatches(&self) -> Vec<(&FamilyProto, &str, &str)> {
+        self.families()
+            .iter()
+            .filter_map(FamilyEntry::family)
+            .filter_map(|family| {
+                let lang = self.primary_language(family);
+                let covered = family
+                    .subsets
+                    .iter()
+                    .any(|s| subset_script(s) == Some(lang.script()));
+                (!covered).then_some((family, lang.id(), lang.script()))
+            })
+            .collect()
+    }
+
+    /// List families with more than one variable font file.
+    ///
+    /// Each entry pairs the family with its bracketed-filename fonts. A roman
+    /// and italic variable font pair is legitimate, so treat this as a list to
+    /// review rather than as errors.
+    pub fn families_with_multiple_vfs(&self) -> Vec<(&FamilyProto, Vec<&FontProto>)> {
+        self.families()
+            .iter()
+            .filter_map(FamilyEntry::family)
+            .filter_map(|family| {
+                let vfs: Vec<_> = family.fonts.iter().filter(|f| f.is_variable()).collect();
+                (vfs.len() > 1).then_some((family, vfs))
+            })
+            .collect()
+    }
+
+    /// Return every axis tag declared across the library with its family count.
+    ///
+    /// Sorted by descending count, ties broken by tag. Both registered and
+    /// custom axes are included.
+    pub fn distinct_axes(&self) -> Vec<(String, usize)> {
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for family in self.families().iter().filter_map(FamilyEntry::family) {
+            let tags: HashSet<&str> = family.axes.iter().map(|a| a.tag()).collect();
+            for tag in tags {
+                *counts.entry(tag).or_default() += 1;
+            }
+        }
+        let mut counts: Vec<_> = counts
+            .into_iter()
+            .map(|(tag, count)| (tag.to_string(), count))
+            .collect();
+        counts.sort_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then_with(|| a.cmp(b)));
+        counts
+    }
+
+    /// Rank families by how much their design space can vary.
+    ///
+    /// The "volume" of a family is the number of declared axes multiplied by
+    /// the product of each axis's normalized span, where an axis's span is
+    /// `(max - min) / max(|min|, |max|)`. Families without axes have volume
+    /// 0. Families are returned most flexible first, ties broken by name.
+    pub fn families_by_design_space_volume(&self) -> Vec<&FamilyProto> {
+        fn volume(family: &FamilyProto) -> f32 {
+            if family.axes.is_empty() {
+                return 0.0;
+            }
+            let spans: f32 = family
+                .axes
+                .iter()
+                .map(|a| {
+                    let magnitude = a.min_value().abs().max(a.max_value().abs());
+                    if magnitude > 0.0 {
+                        (a.max_value() - a.min_value()) / magnitude
+                    } else {
+                        0.0
+                    }
+                })
+                .product();
+            family.axes.len() as f32 * spans
+        }
+        let mut families: Vec<_> = self
+            .families()
+            .iter()
+            .filter_map(FamilyEntry::family)
+            .collect();
+        families.sort_by(|a, b| {
+            volume(b)
+                .total_cmp(&volume(a))
+                .then_with(|| a.name().cmp(b.name()))
+        });
+        families
+    }
+
+    /// Return declared subsets for which no language has usable sample text.
+    ///
+    /// A subset is associated with every language written in its script (see
+    /// [`subset_script`]); it's reported when none of those languages have
+    /// sample text. Subsets that don't map to a script, such as `menu`, are
+    /// not reported.
+    pub fn subsets_without_sample_text(&self, family: &FamilyProto) -> Vec<String> {
+        family
+            .subsets
+            .iter()
+            .filter(|subset| {
+                subset_script(subset).is_some_and(|script| {
+                    !LANGUAGES.values().any(|l| {
+                        l.script.is_some()
+                            && l.script() == script
+                            && language_sample_text(l).is_some()
+                    })
+                })
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Rank families by the number of scripts their subsets cover.
+    ///
+    /// Each family is paired with the sorted, deduplicated display names of
+    /// the scripts its declared subsets cover (see [`subset_script`]); codes
+    /// with no known script name are listed as the code itself. Families are
+    /// sorted by descending script count, ties broken by name.
+    pub fn families_by_script_coverage(&self) -> Vec<(&FamilyProto, Vec<String>)> {
+        let mut coverage: Vec<_> = self
+            .families()
+            .iter()
+            .filter_map(FamilyEntry::family)
+            .map(|family| {
+                let mut names: Vec<String> = family
+                    .subsets
+                    .iter()
+                    .filter_map(|s| subset_script(s))
+                    .map(|code| {
+                        SCRIPTS
+                            .get(code)
+                            .and_then(|s| s.name.clone())
+                            .unwrap_or_else(|| code.to_string())
+                    })
+                    .collect();
+                names.sort();
+                names.dedup();
+                (family, names)
+            })
+            .collect();
+        coverage.sort_by(|(a, a_names), (b, b_names)| {
+            b_names
+                .len()
+                .cmp(&a_names.len())
+                .then_with(|| a.name().cmp(b.name()))
+        });
+        coverage
+    }
+
+    /// Summarise the values each tag is actually given, sorted by tag.
+    ///
+    /// Useful for cross-checking the declared ranges in `tag_metadata()`. If
+    /// tags can't be read nothing is returned.
+    pub fn tag_statistics(&self) -> Vec<TagStat> {
+        let Ok(tags) = self.tags() else {
+            return Vec::new();
+        };
+        let mut stats: Vec<_> = self
+            .taggings_by_tag()
+            .iter()
+            .map(|(tag, group)| {
+                // Groups are sorted by descending value
+                let values: Vec<f32> = group.iter().map(|i| tags[*i].value).collect();
+                TagStat {
+                    tag: tag.clone(),
+                    count: values.len(),
+                    observed_min: values[values.len() - 1],
+                    observed_max: values[0],
+                    mean: values.iter().sum::<f32>() / values.len() as f32,
+                }
+            })
+            .collect();
+        stats.sort_by(|a, b| a.tag.cmp(&b.tag));
+        stats
+    }
+
+    /// Report taggings whose value falls outside their tag's declared range.
+    ///
+    /// Each tagging is joined to `tag_metadata()` by tag name. Values outside
+    /// `[min_value, max_value]` are reported with their metadata; taggings
+    /// whose tag has no metadata at all (often a typo'd tag name) are reported
+    /// with `None`. If either tags or tag metadata can't be read nothing is
+    /// reported.
+    pub fn out_of_range_tags(&self) -> Vec<(&Tagging, Option<&TagMetadata>)> {
+        let (Ok(tags), Ok(_)) = (self.tags(), self.tag_metadata()) else {
+            return Vec::new();
+        };
+        tags.iter()
+            .filter_map(|tagging| match self.tag_metadata_for(&tagging.tag) {
+                Some(m) if tagging.value < m.min_value || tagging.value > m.max_value => {
+                    Some((tagging, Some(m)))
+                }
+                Some(_) => None,
+                None => Some((tagging, None)),
+            })
+            .collect()
+    }
+
+    /// Report taggings whose location doesn't fit their family.
+    ///
+    /// Each tagging's `loc` is parsed and checked against the family it names:
+    /// every axis must be declared by the family and every value must lie
+    /// within the declared range. `ital` needs no axis declaration; 0 is always
+    /// valid and 1 requires an italic font. A `wght` value is also accepted on
+    /// a family without a `wght` axis if a static font has exactly that
+    /// weight. Taggings for unknown families, and all taggings if tags can't
+    /// be read, are skipped. Each entry is the tagging and a reason.
+    pub fn invalid_tag_locations(&self) -> Vec<(&Tagging, String)> {
+        let Ok(tags) = self.tags() else {
+            return Vec::new();
+        };
+        let by_name: HashMap<&str, &FamilyProto> = self
+            .families()
+            .iter()
+            .filter_map(FamilyEntry::family)
+            .map(|f| (f.name(), f))
+            .collect();
+        let mut invalid = Vec::new();
+        for tagging in tags {
+            let Some(family) = by_name.get(tagging.family.as_str()) else {
+                continue;
+            };
+            let coords = match tagging.location() {
+                Ok(location) => location.coordinates,
+                Err(e) => {
+                    invalid.push((tagging, format!("unparseable location: {e}")));
+                    continue;
+                }
+            };
+            for (axis, value) in coords {
+                if let Some(problem) = location_problem(family, &axis, value) {
+                    invalid.push((tagging, problem));
+                }
+            }
+        }
+        invalid
+    }
+
+    /// Check that every coordinate of `loc` is within `family`'s design space.
+    ///
+    /// The rules are those of [`invalid_tag_locations`](Self::invalid_tag_locations):
+    /// each axis must be declared by the family with the value inside its
+    /// range, except that `ital` 0 (or 1, given an italic font) and a `wght`
+    /// matching a static font's weight need no axis. The default location is
+    /// always valid.
+    pub fn is_valid_location(&self, family: &FamilyProto, loc: &DesignspaceLocation) -> bool {
+        loc.coordinates
+            .iter()
+            .all(|(axis, value)| location_problem(family, axis, *value).is_none())
+    }
+
+    /// Count the families each designer contributed to.
+    ///
+    /// Multi-designer families count once for each credited designer. Sorted
+    /// by descending count, ties broken by name.
+    pub fn designer_family_counts(&self) -> Vec<(String, usize)> {
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        for family in self.families().iter().filter_map(FamilyEntry::family) {
+            let mut names = family.designer_names();
+            names.sort();
+            names.dedup();
+            for name in names {
+                *counts.entry(name).or_default() += 1;
+            }
+        }
+        let mut counts: Vec<_> = counts.into_iter().collect();
+        counts.sort_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then_with(|| a.cmp(b)));
+        counts
+    }
+
+    /// Return the families that credit `designer_name`, in the order of
+    /// [`families`](Self::families).
+    ///
+    /// Names are compared case-insensitively with runs of whitespace collapsed,
+    /// against each of the family's [`FamilyExt::designer_names`]. Families
+    /// that failed to parse are skipped.
+    pub fn families_by_designer(&self, designer_name: &str) -> Vec<&FamilyProto> {
+        let normalize = |name: &str| {
+            name.split_whitespace()
+                .map(str::to_lowercase)
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        let wanted = normalize(designer_name);
+        self.families()
+            .iter()
+            .filter_map(FamilyEntry::family)
+            .filter(|family| {
+                family
+                    .designer_names()
+                    .iter()
+                    .any(|name| normalize(name) == wanted)
+            })
+            .collect()
+    }
+
+    /// Report families whose declared category disagrees with their tags.
+    ///
+    /// The classification tag groups consulted are `/Sans/`, `/Serif/`,
+    /// `/Slab/`, `/Script/` and `/Monospace/`, using taggings at the default
+    /// location only. For each family the strongest value per group is taken;
+    /// a group counts as present when that value is at least 50. A conflict
+    /// is reported when some group is present but none of the groups matching
+    /// the family's first category (`SANS_SERIF` → `/Sans/`, `SERIF` →
+    /// `/Serif/` or `/Slab/`, `HANDWRITING` → `/Script/`, `MONOSPACE` →
+    /// `/Monospace/`) is. `DISPLAY` and other categories are not checked.
+    pub fn category_tag_conflicts(&self) -> Vec<(&FamilyProto, String)> {
+        const THRESHOLD: f32 = 50.0;
+        const GROUPS: [&str; 5] = ["/Sans/", "/Serif/", "/Slab/", "/Script/", "/Monospace/"];
+        let Ok(tags) = self.tags() else {
+            return Vec::new();
+        };
+        let mut strongest: HashMap<(&str, &str), f32> = HashMap::new();
+        for tagging in tags.iter().filter(|t| t.loc.is_empty()) {
+            if let Some(group) = GROUPS.iter().find(|g| tagging.tag.starts_with(*g)) {
+                let value = strongest
+                    .entry((tagging.family.as_str(), *group))
+                    .or_insert(f32::MIN);
+                *value = value.max(tagging.value);
+            }
+        }
+        let mut conflicts = Vec::new();
+        for family in self.families().iter().filter_map(FamilyEntry::family) {
+            let expected: &[&str] = match family.category.first().map(String::as_str) {
+                Some("SANS_SERIF") => &["/Sans/"],
+                Some("SERIF") => &["/Serif/", "/Slab/"],
+                Some("HANDWRITING") => &["/Script/"],
+                Some("MONOSPACE") => &["/Monospace/"],
+                _ => continue,
+            };
+            let score = |group: &str| {
+                strongest
+                    .get(&(family.name(), group))
+                    .copied()
+                    .filter(|v| *v >= THRESHOLD)
+            };
+            if expected.iter().any(|g| score(g).is_some()) {
+                continue;
+            }
+            let best = GROUPS
+                .iter()
+                .filter_map(|g| score(g).map(|v| (g, v)))
+                .max_by(|(_, a), (_, b)| a.total_cmp(b));
+            if let Some((group, value)) = best {
+                conflicts.push((
+                    family,
+                    format!(
+                        "category {} but strongest classification tag is {group} ({value})",
+                        family.category[0]
+                    ),
+                ));
+            }
+        }
+        conflicts
+    }
+
+    /// Return a family's tag profile as a fixed-order numeric vector.
+    ///
+    /// The vector has one slot per distinct tag across all taggings, in
+    /// ascending tag-name order, so vectors for different families line up.
+    /// Only default-location taggings are used. Each value is normalized to
+    /// `0.0..=1.0` against the tag's declared range from `tags_metadata.csv`,
+    /// or its observed range across the library when it has no metadata.
+    /// Tags the family lacks are `-1.0`. Returns `None` if the family has no
+    /// taggings or tags can't be read.
+    pub fn tag_vector(&self, family_name: &str) -> Option<Vec<f32>> {
+        let tags = self.tags().ok()?;
+        let declared: HashMap<&str, (f32, f32)> = self
+            .tag_metadata()
+            .unwrap_or_default()
+            .iter()
+            .map(|m| (m.tag.as_str(), (m.min_value, m.max_value)))
+            .collect();
+        let mut ranges: HashMap<&str, (f32, f32)> = HashMap::new();
+        for t in tags.iter().filter(|t| t.loc.is_empty()) {
+            let range = ranges.entry(t.tag.as_str()).or_insert((t.value, t.value));
+            range.0 = range.0.min(t.value);
+            range.1 = range.1.max(t.value);
+        }
+        let mut order: Vec<&str> = ranges.keys().copied().collect();
+        order.sort();
+        let values: HashMap<&str, f32> = tags
+            .iter()
+            .filter(|t| t.loc.is_empty() && t.family == family_name)
+            .map(|t| (t.tag.as_str(), t.value))
+            .collect();
+        if values.is_empty() {
+            return None;
+        }
+        Some(
+            order
+                .iter()
+                .map(|tag| {
+                    let Some(value) = values.get(tag) else {
+                        return -1.0;
+                    };
+                    let (min, max) = declared.get(tag).unwrap_or(&ranges[tag]);
+                    if max > min {
+                        ((value - min) / (max - min)).clamp(0.0, 1.0)
+                    } else {
+                        1.0
+                    }
+                })
+                .collect(),
+        )
+    }
+
+    /// Report designer names that don't resolve to a designers catalog entry.
+    ///
+    /// Each credited designer (the `designer` field split on commas) is
+    /// expected to have `catalog/designers/<slug>/info.pb` in the repo, where
+    /// the slug is the lowercased name with only letters and digits kept.
+    /// Each entry is the family and the unresolved name.
+    pub fn unresolved_designers(&self) -> Vec<(&FamilyProto, String)> {
+        let designers_dir = self.repo_dir.join("catalog/designers");
+        self.families()
+            .iter()
+            .filter_map(FamilyEntry::family)
+            .flat_map(|family| {
+                family
+                    .designer_names()
+                    .into_iter()
+                    .map(move |name| (family, name))
+            })
+            .filter(|(_, name)| {
+                !designers_dir
+                    .join(designer_slug(name))
+                    .join("info.pb")
+                    .is_file()
+            })
+            .collect()
+    }
+
+    /// Write every successfully parsed family as newline-delimited JSON.
+    ///
+    /// Each line is one family in protobuf's JSON mapping, using the proto
+    /// field names (`date_added`, `post_script_name`, ...) as keys. Families
+    /// are written in `families()` order.
+    pub fn write_families_jsonl<W: Write>(&self, mut w: W) -> Result<(), Error> {
+        let options = PrintOptions {
+            proto_field_name: true,
+            ..Default::default()
+        };
+        for family in self.families().iter().filter_map(FamilyEntry::family) {
+            let json = protobuf_json_mapping::print_to_string_with_options(family, &options)
+                .map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+            writeln!(w, "{json}")?;
+        }
+        Ok(())
+    }
+
+    /// Check whether a family's exemplar hinges on its variable font.
+    ///
+    /// This compares [`exemplar`] with the exemplar the family would have if
+    /// its variable fonts were removed, which is what a renderer without
+    /// variable font support would end up using.
+    pub fn exemplar_stability(&self, family: &FamilyProto) -> ExemplarStability {
+        let mut static_only = family.clone();
+        static_only.fonts.retain(|f| !f.is_variable());
+        match (exemplar(family), exemplar(&static_only)) {
+            (_, None) => ExemplarStability::NoStaticFallback,
+            (Some(chosen), Some(fallback)) if chosen == fallback => ExemplarStability::Stable,
+            _ => ExemplarStability::ChangesWithoutVariable,
+        }
+    }
+
+    /// Compare the coverage of two families, e.g. when migrating between them.
+    ///
+    /// Subsets are compared as declared; scripts are those implied by the
+    /// subsets (see [`subset_script`]). Lost and gained coverage are reported
+    /// separately, each sorted.
+    pub fn coverage_delta(&self, from: &FamilyProto, to: &FamilyProto) -> CoverageDelta {
+        fn difference(a: &BTreeSet<&str>, b: &BTreeSet<&str>) -> Vec<String> {
+            a.difference(b).map(|s| s.to_string()).collect()
+        }
+        fn subsets(f: &FamilyProto) -> BTreeSet<&str> {
+            f.subsets.iter().map(String::as_str).collect()
+        }
+        fn scripts(f: &FamilyProto) -> BTreeSet<&str> {
+            f.subsets.iter().filter_map(|s| subset_script(s)).collect()
+        }
+        let (from_subsets, to_subsets) = (subsets(from), subsets(to));
+        let (from_scripts, to_scripts) = (scripts(from), scripts(to));
+        CoverageDelta {
+            lost_subsets: difference(&from_subsets, &to_subsets),
+            gained_subsets: difference(&to_subsets, &from_subsets),
+            lost_scripts: difference(&from_scripts, &to_scripts),
+            gained_scripts: difference(&to_scripts, &from_scripts),
+        }
+    }
+
+    /// Report families whose declared `axes` disagree with their filenames.
+    ///
+    /// Three problems are reported: a bracketed (variable) filename in a
+    /// family with no declared axes, declared axes with no bracketed
+    /// filename, and a bracketed filename whose axis tags (see
+    /// [`filename_axes`]) differ from the declared ones. Each entry is the
+    /// `METADATA.pb` path, the family and a description.
+    pub fn axis_declaration_mismatches(&self) -> Vec<(&Path, &FamilyProto, String)> {
+        let mut mismatches = Vec::new();
+        for FamilyEntry { path, family } in self.families() {
+            let Ok(family) = family else {
+                continue;
+            };
+            let declared: BTreeSet<&str> = family.axes.iter().map(|a| a.tag()).collect();
+            let variable: Vec<_> = family.fonts.iter().filter(|f| f.is_variable()).collect();
+            if variable.is_empty() && !declared.is_empty() {
+                mismatches.push((
+                    path.as_path(),
+                    family,
+                    "axes declared but no variable font file".to_string(),
+                ));
+            }
+            for font in variable {
+                let in_filename: BTreeSet<&str> = filename_axes(font).into_iter().collect();
+                let problem = if declared.is_empty() {
+                    format!("{} is variable but no axes are declared", font.filename())
+                } else if in_filename != declared {
+                    format!(
+                        "{} encodes axes {:?} but {:?} are declared",
+                        font.filename(),
+                        in_filename,
+                        declared
+                    )
+                } else {
+                    continue;
+                };
+                mismatches.push((path.as_path(), family, problem));
+            }
+        }
+        mismatches
+    }
+
+    /// Return sample text to render a family with.
+    ///
+    /// The family's [`primary_language`](GoogleFonts::primary_language) is
+    /// resolved and its richest sample string returned, preferring
+    /// `masthead_full` (see [`language_sample_text`]). Returns `None` only if
+    /// that language has no sample text at all.
+    pub fn sample_text(&self, family: &FamilyProto) -> Option<&str> {
+        language_sample_text(self.primary_language(family))
+    }
+
+    /// Pick sample text for a family that fits within `max_chars` characters.
+    ///
+    /// Candidates are the sample strings of the family's
+    /// [`primary_language`](GoogleFonts::primary_language). The longest one
+    /// not exceeding `max_chars` is returned; if every candidate is too long,
+    /// the shortest is truncated to `max_chars`. Returns `None` if the language
+    /// has no sample text.
+    pub fn sample_text_fitting(&self, family: &FamilyProto, max_chars: usize) -> Option<String> {
+        let fields = sample_text_fields(self.primary_language(family));
+        let len = |s: &&str| s.chars().count();
+        if let Some(fits) = fields
+            .iter()
+            .copied()
+            .filter(|s| len(s) <= max_chars)
+            .max_by_key(len)
+        {
+            return Some(fits.to_string());
+        }
+        fields
+            .into_iter()
+            .min_by_key(len)
+            .map(|s| s.chars().take(max_chars).collect())
+    }
+
+    /// Group successfully parsed families by their `license` string.
+    ///
+    /// Keys are the license values as written in `METADATA.pb` (e.g. `OFL`,
+    /// `APACHE2`, `UFL`); each group keeps `families()` order.
+    pub fn families_by_license(&self) -> BTreeMap<String, Vec<&FamilyProto>> {
+        let mut groups: BTreeMap<String, Vec<&FamilyProto>> = BTreeMap::new();
+        for family in self.families().iter().filter_map(FamilyEntry::family) {
+            groups
+                .entry(family.license().to_string())
+                .or_default()
+                .push(family);
+        }
+        groups
+    }
+
+    /// Return the license implied by the directory holding a family.
+    ///
+    /// This looks for an `ofl`, `apache` or `ufl` component in the path to the
+    /// family's `METADATA.pb` below the repository root; no license file is
+    /// read. Returns `None` if the family isn't part of this repository.
+    pub fn license(&self, family: &FamilyProto) -> Option<License> {
+        let path = self.metadata_path(family)?;
+        let path = path.strip_prefix(&self.repo_dir).unwrap_or(path);
+        let license = path
+            .components()
+            .find_map(|c| match c.as_os_str().to_str() {
+                Some("ofl") => Some(License::Ofl),
+                Some("apache") => Some(License::Apache),
+                Some("ufl") => Some(License::Ufl),
+                _ => None,
+            })
+            .unwrap_or(License::Unknown);
+        Some(license)
+    }
+
+    /// Return a family's description, for showing in a catalog.
+    ///
+    /// `article/ARTICLE.en.html` beside the family's `METADATA.pb` is
+    /// preferred, then `DESCRIPTION.en.html`. Returns `None` if neither can be
+    /// read or the family isn't part of this repository.
+    pub fn family_description(&self, family: &FamilyProto) -> Option<String> {
+        let dir = self.metadata_path(family)?.parent()?;
+        ["article/ARTICLE.en.html", "DESCRIPTION.en.html"]
+            .into_iter()
+            .find_map(|name| fs::read_to_string(dir.join(name)).ok())
+    }
+
+    /// Locate the `METADATA.pb` a family was read from.
+    fn metadata_path(&self, family: &FamilyProto) -> Option<&Path> {
+        family
+            .fonts
+            .first()
+            .and_then(|font| self.family(font))
+            .map(|(p, _)| p)
+    }
+
+    fn family_by_font_file(&self) -> &HashMap<String, usize> {
+        self.family_by_font_file.get_or_init(|| {
+            let families = self.families();
+            let mut index: HashMap<String, usize> = HashMap::new();
+            for (i, FamilyEntry { path, family }) in families.iter().enumerate() {
+                let Ok(family) = family else {
+                    continue;
+                };
+                for font in family.fonts.iter() {
+                    index
+                        .entry(font.filename().to_string())
+                        .and_modify(|j| {
+                            if path < &families[*j].path {
+                                *j = i;
+                            }
+                        })
+                        .or_insert(i);
+                }
+            }
+            index
+        })
+    }
+
+    /// Font filenames declared by more than one family.
+    ///
+    /// Each entry is a filename and the `METADATA.pb` paths declaring it,
+    /// sorted; entries are sorted by filename. [`family`](Self::family)
+    /// resolves such a filename to the family whose path sorts first.
+    pub fn duplicate_font_files(&self) -> Vec<(&str, Vec<&Path>)> {
+        let mut owners: BTreeMap<&str, Vec<&Path>> = BTreeMap::new();
+        for FamilyEntry { path, family } in self.families() {
+            let Ok(family) = family else {
+                continue;
+            };
+            for font in family.fonts.iter() {
+                owners.entry(font.filename()).or_default().push(path);
+            }
+        }
+        owners
+            .into_iter()
+            .filter_map(|(filename, mut paths)| {
+                paths.sort();
+                paths.dedup();
+                (paths.len() > 1).then_some((filename, paths))
+            })
+            .collect()
+    }
+
+    fn family_by_font_file_ci(&self) -> &HashMap<String, usize> {
+        self.family_by_font_file_ci.get_or_init(|| {
+            let families = self.families();
+            let mut index: HashMap<String, usize> = HashMap::new();
+            for (i, FamilyEntry { path, family }) in families.iter().enumerate() {
+                let Ok(family) = family else {
+                    continue;
+                };
+                for font in family.fonts.iter() {
+                    index
+                        .entry(font.filename().to_lowercase())
+                        .and_modify(|j| {
+                            if path < &families[*j].path {
+                                *j = i;
+                            }
+                        })
+                        .or_insert(i);
+                }
+            }
+            index
+        })
+    }
+
+    /// Find the family owning a font file, ignoring case.
+    ///
+    /// Useful when filenames come from case-insensitive filesystems or user
+    /// input, e.g. `roboto[wdth,wght].ttf`. If several families have
+    /// filenames differing only by case, the one whose `METADATA.pb` path
+    /// sorts first wins, so the result is deterministic.
+    pub fn family_by_filename_ci(&self, filename: &str) -> Option<(&Path, &FamilyProto)> {
+        self.family_by_font_file_ci()
+            .get(&filename.to_lowercase())
+            .copied()
+            .map(|i| {
+                let entry = &self.families()[i];
+                (entry.path(), entry.family().unwrap())
+            })
+    }
+
+    fn family_by_name_index(&self) -> &HashMap<String, usize> {
+        self.family_by_name.get_or_init(|| {
+            let mut index = HashMap::new();
+            for (i, entry) in self.families().iter().enumerate() {
+                if let Some(family) = entry.family() {
+                    // Families are sorted by path, so the first one wins
+                    index.entry(family.name().to_string()).or_insert(i);
+                }
+            }
+            index
+        })
+    }
+
+    /// Look up a family by its exact name, e.g. "Roboto Slab".
+    ///
+    /// Returns the path to the family's `METADATA.pb` and the family. Families
+    /// that failed to parse are never matched. If several families share a
+    /// name, the one whose `METADATA.pb` path sorts first wins, as for
+    /// [`family`](Self::family).
+    pub fn family_by_name(&self, name: &str) -> Option<(&Path, &FamilyProto)> {
+        self.family_by_name_index().get(name).copied().map(|i| {
+            let entry = &self.families()[i];
+            (entry.path(), entry.family().unwrap())
+        })
+    }
+
+    /// Iterate over `(name, family)` pairs for every successfully parsed family.
+    ///
+    /// This is the index behind [`family_by_name`](Self::family_by_name), so
+    /// it's cheap to collect into a map keyed by name, and a name shared by
+    /// several families yields only the one `family_by_name` returns. Pairs
+    /// come in no particular order.
+    pub fn families_by_name(&self) -> impl Iterator<Item = (&str, &FamilyProto)> {
+        self.family_by_name_index()
+            .iter()
+            .map(|(name, i)| (name.as_str(), self.families()[*i].family().unwrap()))
+    }
+
+    /// Indices into `tags()` grouped by tag, each group by descending value.
+    fn taggings_by_tag(&self) -> &HashMap<String, Vec<usize>> {
+        self.taggings_by_tag.get_or_init(|| {
+            let Ok(tags) = self.tags() else {
+                return HashMap::new();
+            };
+            let mut index: HashMap<String, Vec<usize>> = HashMap::new();
+            for (i, tagging) in tags.iter().enumerate() {
+                index.entry(tagging.tag.clone()).or_default().push(i);
+            }
+            for group in index.values_mut() {
+                group.sort_by(|a, b| tags[*b].value.total_cmp(&tags[*a].value));
+            }
+            index
+        })
+    }
+
+    /// Indices into `tags()` grouped by family name, in file order.
+    fn taggings_by_family(&self) -> &HashMap<String, Vec<usize>> {
+        self.taggings_by_family.get_or_init(|| {
+            let Ok(tags) = self.tags() else {
+                return HashMap::new();
+            };
+            let mut index: HashMap<String, Vec<usize>> = HashMap::new();
+            for (i, tagging) in tags.iter().enumerate() {
+                index.entry(tagging.family.clone()).or_default().push(i);
+            }
+            index
+        })
+    }
+
+    /// Return every tagging of the named family, in file order.
+    ///
+    /// Taggings at a specific location are included alongside whole-family
+    /// ones. If tags can't be read nothing is returned.
+    pub fn tags_for_family(&self, family_name: &str) -> Vec<&Tagging> {
+        let (Some(group), Ok(tags)) = (self.taggings_by_family().get(family_name), self.tags())
+        else {
+            return Vec::new();
+        };
+        group.iter().map(|i| &tags[*i]).collect()
+    }
+
+    /// Return the named family's taggings grouped by their `loc` string.
+    ///
+    /// Whole-family taggings are grouped under the empty string.
+    pub fn tags_for_family_grouped(&self, family_name: &str) -> HashMap<String, Vec<&Tagging>> {
+        let mut grouped: HashMap<String, Vec<&Tagging>> = HashMap::new();
+        for tagging in self.tags_for_family(family_name) {
+            grouped
+                .entry(tagging.loc.clone())
+                .or_default()
+                .push(tagging);
+        }
+        grouped
+    }
+
+    /// Return the families tagged with `tag` and their values, strongest first.
+    ///
+    /// When `min_value` is given only values at or above it are returned.
+    /// Taggings at a specific location are included alongside whole-family
+    /// ones, so a family may appear more than once. If tags can't be read
+    /// nothing is returned.
+    pub fn families_with_tag(&self, tag: &str, min_value: Option<f32>) -> Vec<(&str, f32)> {
+        let (Some(group), Ok(tags)) = (self.taggings_by_tag().get(tag), self.tags()) else {
+            return Vec::new();
+        };
+        group
+            .iter()
+            .map(|i| &tags[*i])
+            .take_while(|t| min_value.is_none_or(|min| t.value >= min))
+            .map(|t| (t.family.as_str(), t.value))
+            .collect()
+    }
+
+    /// Find families whose name resembles `query`, best match first.
+    ///
+    /// Names are compared ignoring case and whitespace. An exact match scores
+    /// 1.0; otherwise the score is the better of a substring score (the
+    /// shorter name's share of the longer, when one contains the other) and
+    /// one minus the edit distance over the longer length. At most ten
+    /// candidates with a positive score are returned.
+    pub fn find_family_fuzzy(&self, query: &str) -> Vec<(&FamilyProto, f64)> {
+        let query = normalize_family_name(query);
+        let mut candidates: Vec<_> = self
+            .family_by_name_index()
+            .iter()
+            .map(|(name, i)| {
+                let family = self.families()[*i].family().unwrap();
+                (
+                    family,
+                    family_name_similarity(&query, &normalize_family_name(name)),
+                )
+            })
+            .filter(|(_, score)| *score > 0.0)
+            .collect();
+        candidates
+            .sort_by(|(a, sa), (b, sb)| sb.total_cmp(sa).then_with(|| a.name().cmp(b.name())));
+        candidates.truncate(10);
+        candidates
+    }
+
+    /// Given a `FontProto`, return the family it belongs to.
+    ///
+    /// If the provided font is known (by filename) this returns `Some((path, family))`
+    /// where `path` is the path to the family's `METADATA.pb` and `family` is
+    /// a borrowed `FamilyProto`. Returns `None` if the font is not present in
+    /// the discovered families. If several families declare the filename (see
+    /// [`duplicate_font_files`](Self::duplicate_font_files)), the one whose
+    /// path sorts first wins.
+    pub fn family(&self, font: &FontProto) -> Option<(&Path, &FamilyProto)> {
+        self.family_by_font_file()
+            .get(font.filename())
+            .copied()
+            .map(|i| {
+                let entry = &self.families()[i];
+                (entry.path(), entry.family().unwrap())
+            })
+    }
+    /// Find the path to the font binary for a `FontProto`.
+    ///
+    /// This resolves the font's family, then constructs the filesystem path
+    /// to the font file (sibling to the family's `METADATA.pb`). If the
+    /// resulting file exists its `PathBuf` is returned. Otherwise any
+    /// [binary search roots](GoogleFontsBuilder::binary_search_roots) are
+    /// searched as by [`GoogleFonts::find_font_binary_in`]. If the file cannot
+    /// be found `None` is returned.
+    pub fn find_font_binary(&self, font: &FontProto) -> Option<PathBuf> {
+        self.find_font_binary_in(font, &self.binary_search_roots)
+    }
+
+    /// Find a font binary beside its `METADATA.pb`, or else under `roots`.
+    ///
+    /// If the sibling file is missing, each root is searched recursively, in
+    /// order, for a file with the font's filename; the first match is
+    /// returned. This supports flattened distributions where binaries live in
+    /// a tree parallel to the metadata.
+    pub fn find_font_binary_in(&self, font: &FontProto, roots: &[PathBuf]) -> Option<PathBuf> {
+        if let Some((family_path, _)) = self.family(font) {
+            let font_file = font_path(family_path, font);
+            if font_file.exists() {
+                return Some(font_file);
+            }
+        }
+        roots.iter().find_map(|root| {
+            WalkDir::new(root)
+                .into_iter()
+                .filter_map(|d| d.ok())
+                .find(|d| d.file_type().is_file() && d.file_name() == font.filename())
+                .map(|d| d.into_path())
+        })
+    }
+
+    /// Read the bytes of a font binary, located as by
+    /// [`find_font_binary`](Self::find_font_binary).
+    ///
+    /// Returns `Ok(None)` if there's no binary for the font, and an error only
+    /// if one is found but can't be read.
+    pub fn read_font_binary(&self, font: &FontProto) -> Result<Option<Vec<u8>>, Error> {
+        let Some(path) = self.find_font_binary(font) else {
+            return Ok(None);
+        };
+        match fs::read(path) {
+            Ok(data) => Ok(Some(data)),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Return the filenames of fonts in `family` that have no binary on disk.
+    ///
+    /// Fonts are expected beside the family's `METADATA.pb`, as for
+    /// [`GoogleFonts::find_font_binary`], but nothing is printed. A family
+    /// that isn't part of this repository has nothing to report.
+    pub fn missing_font_binaries<'a>(&self, family: &'a FamilyProto) -> Vec<&'a str> {
+        let Some(metadata) = self.metadata_path(family) else {
+            return Vec::new();
+        };
+        family
+            .fonts
+            .iter()
+            .filter(|font| !font_path(metadata, font).exists())
+            .map(|font| font.filename())
+            .collect()
+    }
+
+    /// Return the font binaries in `family`'s directory that it doesn't
+    /// declare, sorted.
+    ///
+    /// This is the inverse of [`missing_font_binaries`](Self::missing_font_binaries):
+    /// `.ttf` and `.otf` files beside the family's `METADATA.pb` whose
+    /// filename no font lists. Subdirectories aren't searched. A family that
+    /// isn't part of this repository has nothing to report; it's an error if
+    /// the directory can't be read.
+    pub fn orphan_font_files(&self, family: &FamilyProto) -> Result<Vec<PathBuf>, Error> {
+        let Some(dir) = self.metadata_path(family).and_then(Path::parent) else {
+            return Ok(Vec::new());
+        };
+        let mut orphans = Vec::new();
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            let is_font = path.extension().is_some_and(|ext| {
+                ext.eq_ignore_ascii_case("ttf") || ext.eq_ignore_ascii_case("otf")
+            });
+            let declared = path
+                .file_name()
+                .is_some_and(|name| family.fonts.iter().any(|f| name == f.filename()));
+            if is_font && path.is_file() && !declared {
+                orphans.push(path);
+            }
+        }
+        orphans.sort();
+        Ok(orphans)
+    }
+
+    /// Map each family name to the font files it declares but lacks on disk.
+    ///
+    /// Families with all their binaries present are omitted, so an empty map
+    /// means the checkout is complete.
+    pub fn validate_repo(&self) -> BTreeMap<&str, Vec<&str>> {
+        self.families()
+            .iter()
+            .filter_map(FamilyEntry::family)
+            .filter_map(|family| {
+                let missing = self.missing_font_binaries(family);
+                (!missing.is_empty()).then(|| (family.name(), missing))
+            })
+            .collect()
+    }
+
+    /// Sum the on-disk size of every font binary a family declares.
+    ///
+    /// Each font is resolved with [`find_font_binary`](Self::find_font_binary);
+    /// binaries that can't be found count as zero.
+    pub fn family_binary_size(&self, family: &FamilyProto) -> Result<u64, Error> {
+        family
+            .fonts
+            .iter()
+            .filter_map(|font| self.find_font_binary(font))
+            .map(|path| fs::metadata(path).map(|m| m.len()))
+            .sum()
+    }
+
+    /// Sum the on-disk size of every font binary across successfully parsed
+    /// families, for capacity planning. Missing binaries count as zero.
+    pub fn total_binary_size(&self) -> Result<u64, Error> {
+        self.families()
+            .iter()
+            .filter_map(FamilyEntry::family)
+            .map(|family| self.family_binary_size(family))
+            .sum()
+    }
+
+    /// Work out which languages a family supports from its fonts' coverage.
+    ///
+    /// The family's [`exemplar`] binary is located with
+    /// [`find_font_binary`](Self::find_font_binary) and every known language
+    /// whose base exemplar characters are all in its cmap is returned, sorted
+    /// by id. Languages without base exemplar characters are never returned.
+    /// Unlike the `languages` field this reflects what the font actually
+    /// contains. It's an error if the binary can't be found or parsed.
+    #[cfg(feature = "skrifa")]
+    pub fn computed_languages(&self, family: &FamilyProto) -> Result<Vec<&LanguageProto>, Error> {
+        use skrifa::{FontRef, MetadataProvider};
+        let data = self.exemplar_binary(family)?;
+        let font =
+            FontRef::new(&data).map_err(|e| Error::new(ErrorKind::InvalidData, e.to_string()))?;
+        let charmap = font.charmap();
+        let mut languages: Vec<&LanguageProto> = LANGUAGES
+            .values()
+            .map(|l| &**l)
+            .filter(|l| !language_base_chars(l).is_empty())
+            .filter(|l| charmap_covers_language(&charmap, l))
+            .collect();
+        languages.sort_by(|a, b| a.id().cmp(b.id()));
+        Ok(languages)
+    }
+
+    /// Compare the subsets a family declares with what its font covers.
+    ///
+    /// The family's [`exemplar`] binary is opened and checked against the
+    /// `unicode-range` of every subset [`subset_unicode_range`] knows; a subset
+    /// counts as covered when the cmap maps at least half of its codepoints.
+    /// Subsets without a known range (`menu`, CJK, `math`, ...) are never
+    /// reported. Both lists are sorted. It's an error if the binary can't be
+    /// found or parsed.
+    #[cfg(feature = "skrifa")]
+    pub fn subset_mismatch(&self, family: &FamilyProto) -> Result<SubsetMismatch, Error> {
+        use skrifa::{FontRef, MetadataProvider};
+        let data = self.exemplar_binary(family)?;
+        let font =
+            FontRef::new(&data).map_err(|e| Error::new(ErrorKind::InvalidData, e.to_string()))?;
+        let charmap = font.charmap();
+        let mut mismatch = SubsetMismatch::default();
+        for subset in subsets::ranged_subsets() {
+            let range = subset_unicode_range(subset).expect("Ranged subsets have a range");
+            let (mut total, mut mapped) = (0, 0);
+            for cp in subsets::unicode_range_codepoints(range) {
+                total += 1;
+                if charmap.map(cp).is_some() {
+                    mapped += 1;
+                }
+            }
+            let covered = total > 0 && mapped * 2 >= total;
+            let declared = family.subsets.iter().any(|s| s == subset);
+            if declared && !covered {
+                mismatch.uncovered.push(subset.to_string());
+            } else if covered && !declared {
+                mismatch.undeclared.push(subset.to_string());
+            }
+        }
+        mismatch.uncovered.sort();
+        mismatch.undeclared.sort();
+        Ok(mismatch)
+    }
+
+    /// Read the binary of the family's [`exemplar`] font.
+    #[cfg(feature = "skrifa")]
+    fn exemplar_binary(&self, family: &FamilyProto) -> Result<Vec<u8>, Error> {
+        let data = match exemplar(family) {
+            Some(font) => self.read_font_binary(font)?,
+            None => None,
+        };
+        data.ok_or_else(|| {
+            Error::new(
+                ErrorKind::NotFound,
+                format!("No font binary for {}", family.name()),
+            )
+        })
+    }
+
+    /// Return every language written in `script`, most populous first.
+    ///
+    /// Languages that don't declare a script are never included.
+    pub fn languages_for_script(&self, script: &str) -> Vec<&LanguageProto> {
+        languages_using_script(script)
+    }
+
+    /// Return the languages written in `script` with a population of at
+    /// least `min_pop`, most populous first.
+    ///
+    /// Like [`languages_for_script`](Self::languages_for_script), but skips
+    /// the long tail of small languages, e.g. when choosing a handful of
+    /// samples to render.
+    pub fn languages_with_min_population(&self, script: &str, min_pop: i64) -> Vec<&LanguageProto> {
+        languages_using_script(script)
+            .into_iter()
+            .filter(|l| i64::from(l.population()) >= min_pop)
+            .collect()
+    }
+
+    /// Our best guess at the primary language for this family
+    ///
+    /// Meant to be a good choice for things like rendering a sample string
+    /// Guess the primary language for a family.
+    ///
+    /// The heuristic is:
+    /// 1. If the family declares a `primary_language` that maps to a known
+    ///    language, return that.
+    /// 2. Otherwise if the family lists known `languages`, pick the most
+    ///    populous of them, preferring the earlier listed on a tie.
+    /// 3. Otherwise if the family declares a `primary_script`, pick the most
+    ///    populous language using that script.
+    /// 4. Fall back to `en_Latn` if nothing else matches.
+    ///
+    /// This is intended as a best-effort choice to select a reasonable
+    /// language for rendering sample text, not as an authoritative mapping.
+    /// Use [`GoogleFonts::primary_language_with_reason`] to find out which
+    /// step was taken.
+    pub fn primary_language(&self, family: &FamilyProto) -> &LanguageProto {
+        self.primary_language_with_reason(family).0
+    }
+
+    /// Iterate over every successfully parsed family with its
+    /// [`primary_language`](Self::primary_language), in the order of
+    /// [`families`](Self::families).
+    pub fn families_with_language(&self) -> impl Iterator<Item = (&FamilyProto, &LanguageProto)> {
+        self.families()
+            .iter()
+            .filter_map(FamilyEntry::family)
+            .map(|family| (family, self.primary_language(family)))
+    }
+
+    /// Guess the primary language for a family, preferring one spoken in `region`.
+    ///
+    /// This follows [`GoogleFonts::primary_language`], except that when the
+    /// language would be picked from the family's `primary_script` by
+    /// population (including when the declared `primary_language` is
+    /// unknown), a language using that script and listing `region` (a
+    /// [`RegionProto`] id such as "IN", compared case-insensitively) is
+    /// preferred, most populous first. If none does, the population heuristic
+    /// stands.
+    pub fn primary_language_for_region(
+        &self,
+        family: &FamilyProto,
+        region: &str,
+    ) -> &LanguageProto {
+        let lang = self.primary_language(family);
+        let known = |id: &str| self.language(id).is_some();
+        let declared = (family.has_primary_language() && known(family.primary_language()))
+            || family.languages.iter().any(|id| known(id));
+        if declared || !family.has_primary_script() {
+            return lang;
+        }
+        self.languages_for_script(family.primary_script())
+            .into_iter()
+            .find(|l| l.region.iter().any(|r| r.eq_ignore_ascii_case(region)))
+            .unwrap_or(lang)
+    }
+
+    /// Guess the primary language for a family, and say how it was chosen.
+    ///
+    /// This follows the same heuristic as [`GoogleFonts::primary_language`].
+    /// If the family declares a `primary_language` that isn't known the
+    /// reason is [`LanguageReason::InvalidDeclaredLanguage`], whichever
+    /// fallback then supplied the language.
+    pub fn primary_language_with_reason(
+        &self,
+        family: &FamilyProto,
+    ) -> (&LanguageProto, LanguageReason) {
+        // Probe primary lang, primary script, then default baselessly to latin
+        let mut invalid_declared = false;
+        if family.has_primary_language() {
+            if let Some(lang) = self.language(family.primary_language()) {
+                return (lang, LanguageReason::DeclaredLanguage);
+            }
+            invalid_declared = true;
+        }
+        let reason = |fallback| {
+            if invalid_declared {
+                LanguageReason::InvalidDeclaredLanguage
+            } else {
+                fallback
+            }
+        };
+        // Next the most populous of the languages the family says it supports;
+        // on a tie the one listed first wins
+        let declared = family
+            .languages
+            .iter()
+            .filter_map(|id| self.language(id))
+            .reduce(|acc, e| {
+                if acc.population() >= e.population() {
+                    acc
+                } else {
+                    e
+                }
+            });
+        if let Some(lang) = declared {
+            return (lang, reason(LanguageReason::LanguagesFallback));
+        }
+        if family.has_primary_script() {
+            // If our script matches many languages pick the one with the highest population
+            if let Some(lang) = self
+                .languages_for_script(family.primary_script())
+                .first()
+                .copied()
+            {
+                return (lang, reason(LanguageReason::ScriptFallback));
+            }
+        }
+        let lang = self
+            .language("en_Latn")
+            .unwrap_or_else(|| panic!("Not even our final fallback worked for {}", family.name()));
+        (lang, reason(LanguageReason::DefaultLatin))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::fs;
+
+    use super::*;
+
+    fn testdata_dir() -> std::path::PathBuf {
+        // cargo test seems to run in the project directory
+        // VSCode test seems to run in the workspace directory
+        // probe for the file we want in hopes of finding it regardless
+
+        ["./resources/testdata", "../resources/testdata"]
+            .iter()
+            .map(std::path::PathBuf::from)
+            .find(|pb| pb.exists())
+            .unwrap()
+    }
+
+    fn testdata_file_content(relative_path: &str) -> String {
+        let mut p = testdata_dir();
+        p.push(relative_path);
+        fs::read_to_string(p).unwrap()
+    }
+
+    #[test]
+    fn roboto_exemplar() {
+        let roboto = read_family(&testdata_file_content("roboto-metadata.pb")).unwrap();
+        let exemplar = exemplar(&roboto).unwrap();
+        assert_eq!("Roboto[wdth,wght].ttf", exemplar.filename());
+    }
+
+    #[test]
+    fn wix_exemplar() {
+        let roboto = read_family(&testdata_file_content("wixmadefortext-metadata.pb")).unwrap();
+        let exemplar = exemplar(&roboto).unwrap();
+        assert_eq!("WixMadeforText[wght].ttf", exemplar.filename());
+    }
+
+    #[test]
+    fn parse_roboto_metadata() {
+        read_family(&testdata_file_content("roboto-metadata.pb")).unwrap();
+    }
+
+    #[test]
+    fn parse_wix_metadata() {
+        // Has the undocumented position field
+        read_family(&testdata_file_content("wixmadefortext-metadata.pb")).unwrap();
+    }
+
+    #[test]
+    fn parse_unknown_nested_block() {
+        let family = read_family(
+            r#"
+            name: "Test Sans"
+            designer: "Jane Doe"
+            license: "OFL"
+            layout {
+              position { x: 1 y: 2 }
+              label: "a } in a string"
+            }
+            future_field: "x"
+            category: "SANS_SERIF"
+            date_added: "2024-01-01"
+            fonts {
+              name: "Test Sans"
+              style: "normal"
+              weight: 400
+              filename: "TestSans-Regular.ttf"
+              post_script_name: "TestSans-Regular"
+              full_name: "Test Sans Regular"
+              position { x: 1 }
+              future_field: 2
+            }
+            "#,
+        )
+        .unwrap();
+        assert_eq!("Test Sans", family.name());
+        assert_eq!(vec!["SANS_SERIF".to_string()], family.category);
+        assert_eq!("TestSans-Regular.ttf", family.fonts[0].filename());
+    }
+
+    #[test]
+    fn parse_primary_lang_script_metadata() {
+        let family = read_family(&testdata_file_content("kosugimaru-metadata.pb")).unwrap();
+        assert_eq!(
+            ("Jpan", "Invalid"),
+            (family.primary_script(), family.primary_language())
+        );
+    }
+
+    #[test]
+    fn parse_tag3() {
+        Tagging::from_str("Roboto Slab, /quant/stroke_width_min, 26.31").expect("To parse");
+    }
+
+    #[test]
+    fn parse_tag4() {
+        Tagging::from_str("Roboto Slab, wght@100, /quant/stroke_width_min, 26.31")
+            .expect("To parse");
+    }
+
+    #[test]
+    fn parse_tag_quoted() {
+        Tagging::from_str("Georama, \"ital,wght@1,100\", /quant/stroke_width_min, 16.97")
+            .expect("To parse");
+    }
+
+    #[test]
+    fn parse_tag_quoted2() {
+        Tagging::from_str("\"\",t,1").expect("To parse");
+    }
+
+    #[test]
+    fn roboto_css_font_stack() {
+        let roboto = read_family(&testdata_file_content("roboto-metadata.pb")).unwrap();
+        assert_eq!("\"Roboto\", sans-serif", css_font_stack(&roboto));
+    }
+
+    #[test]
+    fn roboto_filename_axis_count() {
+        let roboto = read_family(&testdata_file_content("roboto-metadata.pb")).unwrap();
+        let counts: Vec<_> = roboto.fonts.iter().map(filename_axis_count).collect();
+        assert_eq!(vec![2, 2], counts);
+        let kosugi = read_family(&testdata_file_content("kosugimaru-metadata.pb")).unwrap();
+        assert_eq!(0, filename_axis_count(&kosugi.fonts[0]));
+    }
+
+    #[test]
+    fn declared_primary_language_keeps_invalid() {
+        let family = read_family(&testdata_file_content("kosugimaru-metadata.pb")).unwrap();
+        assert_eq!(Some("Invalid"), declared_primary_language(&family));
+    }
+
+    /// Create a fresh repo root under the system temp dir whose `tags/all`
+    /// holds one CSV file per `(name, contents)` pair.
+    fn temp_repo_with_tags(test_name: &str, files: &[(&str, &str)]) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("gf-metadata-{test_name}"));
+        let _ = fs::remove_dir_all(&root);
+        let tag_dir = root.join("tags/all");
+        fs::create_dir_all(&tag_dir).unwrap();
+        for (name, contents) in files {
+            fs::write(tag_dir.join(name), contents).unwrap();
+        }
+        root
+    }
+
+    /// Fill in the required fields a test's `METADATA.pb` leaves out, so a
+    /// fixture only needs to spell out what its test is about. Content that
+    /// doesn't parse at all is returned unchanged.
+    fn complete_metadata(metadata: &str) -> String {
+        let descriptor = <FamilyProto as protobuf::MessageFull>::descriptor();
+        let metadata = rename_legacy_fields(metadata);
+        let mut family = FamilyProto::new();
+        let stripped = strip_unknown_fields(&metadata, &descriptor);
+        if protobuf::text_format::merge_from_str(&mut family, &stripped).is_err() {
+            return metadata.into_owned();
+        }
+        if !family.has_name() {
+            family.set_name("Test Family".to_string());
+        }
+        if !family.has_designer() {
+            family.set_designer("Test Designer".to_string());
+        }
+        if !family.has_license() {
+            family.set_license("OFL".to_string());
+        }
+        if !family.has_date_added() {
+            family.set_date_added("2020-01-01".to_string());
+        }
+        let family_name = family.name().to_string();
+        for font in family.fonts.iter_mut() {
+            if !font.has_name() {
+                font.set_name(family_name.clone());
+            }
+            if !font.has_style() {
+                font.set_style("normal".to_string());
+            }
+            if !font.has_weight() {
+                font.set_weight(400);
+            }
+            if !font.has_filename() {
+                let italic = if font.style() == "italic" {
+                    "Italic"
+                } else {
+                    ""
+                };
+                font.set_filename(format!(
+                    "{}-{}{italic}.ttf",
+                    family_name.replace(' ', ""),
+                    font.weight()
+                ));
+            }
+            if !font.has_post_script_name() {
+                let stem = font.filename().split('.').next().unwrap_or_default();
+                font.set_post_script_name(stem.replace(['[', ']', ','], ""));
+            }
+            if !font.has_full_name() {
+                font.set_full_name(format!("{} {}", font.name(), font.weight()));
+            }
+        }
+        write_family(&family)
+    }
+
+    /// Parse a test fixture, filling in required fields as for
+    /// [`complete_metadata`].
+    fn test_family(metadata: &str) -> FamilyProto {
+        read_family(&complete_metadata(metadata)).unwrap()
+    }
+
+    /// Write `METADATA.pb` files under `root`, one per `(directory,
+    /// content)` pair, completed as by [`complete_metadata`].
+    fn write_families(root: &Path, families: &[(&str, &str)]) {
+        for (dir, metadata) in families {
+            let dir = root.join(dir);
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(dir.join("METADATA.pb"), complete_metadata(metadata)).unwrap();
+        }
+    }
+
+    /// A fresh repository holding the given families, as for [`write_families`].
+    fn temp_repo_with_families(test_name: &str, families: &[(&str, &str)]) -> PathBuf {
+        let root = temp_repo_with_tags(test_name, &[]);
+        write_families(&root, families);
+        root
+    }
+
+    #[test]
+    fn read_tags_reports_bad_line() {
+        let root = temp_repo_with_tags(
+            "read_tags_reports_bad_line",
+            &[(
+                "families.csv",
+                "Roboto, /Quality/Drawing, 90\n\n   \nRoboto, /Quality/Drawing, nope\n",
+            )],
+        );
+        let err = read_tags(&root).unwrap_err();
+        assert!(matches!(err, GfError::Csv { line: 4, .. }), "{err}");
+        assert!(err.to_string().contains("families.csv:4"), "{err}");
+        assert!(matches!(
+            read_tag_metadata(&root),
+            Err(GfError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn write_family_round_trips() {
+        for file in [
+            "roboto-metadata.pb",
+            "wixmadefortext-metadata.pb",
+            "kosugimaru-metadata.pb",
+        ] {
+            let family = read_family(&testdata_file_content(file)).unwrap();
+            let written = write_family(&family);
+            assert_eq!(family, read_family(&written).unwrap(), "{file}");
+        }
+    }
+
+    #[test]
+    fn write_family_sorts_map_entries() {
+        let metadata = complete_metadata(
+            r#"
+            name: "Roboto"
+            registry_default_overrides { key: "wght" value: 400 }
+            registry_default_overrides { key: "GRAD" value: 0 }
+            registry_default_overrides { key: "opsz" value: 14 }
+            registry_default_overrides { key: "wdth" value: 100 }
+            registry_default_overrides { key: "XTRA" value: 468 }
+            sample_glyphs { key: "Numerals" value: "0123456789" }
+            sample_glyphs { key: "Latin" value: "AaBb" }
+            sample_glyphs { key: "Arrows" value: "←→" }
+            "#,
+        );
+        let family = read_family(&metadata).unwrap();
+        let written = write_family(&family);
+        assert_eq!(family, read_family(&written).unwrap());
+        for _ in 0..10 {
+            // Each parse builds maps with a fresh hash seed.
+            assert_eq!(written, write_family(&read_family(&metadata).unwrap()));
+        }
+        let keys: Vec<_> = written
+            .lines()
+            .filter_map(|l| l.strip_prefix("  key: "))
+            .collect();
+        assert_eq!(
+            vec![
+                "\"GRAD\"",
+                "\"XTRA\"",
+                "\"opsz\"",
+                "\"wdth\"",
+                "\"wght\"",
+                "\"Arrows\"",
+                "\"Latin\"",
+                "\"Numerals\""
+            ],
+            keys
+        );
+    }
+
+    #[test]
+    fn write_family_omits_empty_blocks() {
+        let mut family = read_family(&testdata_file_content("roboto-metadata.pb")).unwrap();
+        family.subsets.clear();
+        family.axes.clear();
+        let written = write_family(&family);
+        assert!(!written.contains("subsets"), "{written}");
+        assert!(!written.contains("axes"), "{written}");
+        assert!(
+            written.contains("fonts {\n  name: \"Roboto\"\n"),
+            "{written}"
+        );
+    }
+
+    #[test]
+    fn tagging_display_round_trips() {
+        for line in [
+            "Georama, \"ital,wght@1,100\", /quant/stroke_width_min, 16.97",
+            "Roboto Slab, /quant/stroke_width_min, 26.31",
+        ] {
+            let tagging = Tagging::from_str(line).unwrap();
+            assert_eq!(tagging, Tagging::from_str(&tagging.to_string()).unwrap());
+        }
+        let quoted = Tagging::from_str("Georama, \"ital,wght@1,100\", /t, 1").unwrap();
+        assert_eq!("Georama,\"ital,wght@1,100\",/t,1", quoted.to_string());
+        let no_loc = Tagging::from_str("Roboto Slab, /t, 1").unwrap();
+        assert_eq!("Roboto Slab,/t,1", no_loc.to_string());
+    }
+
+    #[test]
+    fn csv_values_escaped_quotes() {
+        assert_eq!(
+            vec!["He said \"hi\"", "x", "1"],
+            csv_values("\"He said \"\"hi\"\"\",x,1")
+        );
+        let tagging = Tagging::from_str("\"He said \"\"hi\"\"\",x,1").expect("To parse");
+        assert_eq!("He said \"hi\"", tagging.family);
+        assert_eq!(tagging, Tagging::from_str(&tagging.to_string()).unwrap());
+    }
+
+    #[test]
+    fn csv_values_unquoted() {
+        assert_eq!(vec!["a", "", "b c", "1"], csv_values("a, , b c ,1"));
+        assert!(matches!(csv_values("a,b")[0], Cow::Borrowed("a")));
+    }
+
+    #[test]
+    fn select_font_prefers_width() {
+        let family = test_family(
+            r#"
+            name: "Test Sans"
+            fonts { style: "normal" weight: 400 filename: "TestSans-Regular.ttf" }
+            fonts { style: "normal" weight: 400 filename: "TestSansCondensed-Regular.ttf" }
+            "#,
+        );
+        let pick = |width| {
+            select_font_with_width(&family, FontStyle::Normal, 400, width)
+                .unwrap()
+                .filename()
+        };
+        assert_eq!("TestSansCondensed-Regular.ttf", pick(75));
+        assert_eq!("TestSans-Regular.ttf", pick(100));
+        assert_eq!(
+            "TestSans-Regular.ttf",
+            exemplar(&family).unwrap().filename()
+        );
+    }
+
+    #[test]
+    fn parse_designspace_locations() {
+        let loc: DesignspaceLocation = "wght@100".parse().unwrap();
+        assert_eq!(vec![("wght".to_string(), 100.0)], loc.coordinates);
+
+        let loc: DesignspaceLocation = "\"ital,wght@1,700\"".parse().unwrap();
+        assert_eq!(
+            vec![("ital".to_string(), 1.0), ("wght".to_string(), 700.0)],
+            loc.coordinates
+        );
+        assert_eq!(Some(700.0), loc.value("wght"));
+
+        let loc: DesignspaceLocation = "".parse().unwrap();
+        assert!(loc.is_default());
+
+        assert!("wght@bold".parse::<DesignspaceLocation>().is_err());
+    }
+
+    #[test]
+    fn missing_font_binaries_reported() {
+        let root = temp_repo_with_families(
+            "missing_font_binaries_reported",
+            &[(
+                "ofl/testsans",
+                r#"
+                name: "Test Sans"
+                fonts { style: "normal" weight: 400 filename: "TestSans-Regular.ttf" }
+                fonts { style: "italic" weight: 400 filename: "TestSans-Italic.ttf" }
+                "#,
+            )],
+        );
+        let family_dir = root.join("ofl/testsans");
+        fs::write(family_dir.join("TestSans-Regular.ttf"), b"").unwrap();
+
+        let gf = GoogleFonts::new(root.clone(), None);
+        let family = gf.families()[0].family().unwrap();
+        assert_eq!(
+            vec!["TestSans-Italic.ttf"],
+            gf.missing_font_binaries(family)
+        );
+        let binaries: Vec<_> = gf
+            .all_fonts()
+            .filter_map(|(_, font)| gf.find_font_binary(font))
+            .collect();
+        assert_eq!(1, binaries.len());
+        assert_eq!(
+            BTreeMap::from([("Test Sans", vec!["TestSans-Italic.ttf"])]),
+            gf.validate_repo()
+        );
+
+        let dist = root.join("dist/fonts/ttf");
+        fs::create_dir_all(&dist).unwrap();
+        fs::write(dist.join("TestSans-Italic.ttf"), b"").unwrap();
+        let italic = &family.fonts[1];
+        assert!(gf.find_font_binary(italic).is_none());
+        assert_eq!(
+            Some(dist.join("TestSans-Italic.ttf")),
+            gf.find_font_binary_in(italic, &[root.join("dist")])
+        );
+        let gf = GoogleFonts::builder()
+            .repo(root.clone())
+            .glob_filter("ofl/testsans")
+            .binary_search_roots(vec![root.join("dist")])
+            .build();
+        let italic = &gf.families()[0].family().unwrap().fonts[1];
+        assert_eq!(
+            Some(dist.join("TestSans-Italic.ttf")),
+            gf.find_font_binary(italic)
+        );
+    }
+
+    #[test]
+    fn languages_for_script_sorted_by_population() {
+        let gf = GoogleFonts::new(PathBuf::from("/nonexistent"), None);
+        let langs = gf.languages_for_script("Jpan");
+        assert!(!langs.is_empty());
+        assert!(langs.iter().all(|l| l.script() == "Jpan"));
+        assert!(
+            langs
+                .windows(2)
+                .all(|w| w[0].population() >= w[1].population())
+        );
+        assert!(gf.languages_for_script("").is_empty());
+    }
+
+    #[test]
+    fn primary_language_reason_for_invalid_declaration() {
+        let gf = GoogleFonts::new(PathBuf::from("/nonexistent"), None);
+        let kosugi = read_family(&testdata_file_content("kosugimaru-metadata.pb")).unwrap();
+        let (lang, reason) = gf.primary_language_with_reason(&kosugi);
+        assert_eq!(LanguageReason::InvalidDeclaredLanguage, reason);
+        assert_eq!("Jpan", lang.script());
+
+        let roboto = read_family(&testdata_file_content("roboto-metadata.pb")).unwrap();
+        let (lang, reason) = gf.primary_language_with_reason(&roboto);
+        assert_eq!(
+            ("en_Latn", LanguageReason::DefaultLatin),
+            (lang.id(), reason)
+        );
+
+        let mut listed = roboto.clone();
+        listed.languages = vec![
+            "xx_Unknown".to_string(),
+            "fr_Latn".to_string(),
+            "hi_Deva".to_string(),
+        ];
+        listed.set_primary_script("Latn".to_string());
+        let (lang, reason) = gf.primary_language_with_reason(&listed);
+        assert_eq!(
+            ("hi_Deva", LanguageReason::LanguagesFallback),
+            (lang.id(), reason)
+        );
+    }
+
+    #[test]
+    fn unreadable_metadata_is_an_error() {
+        let root = temp_repo_with_families(
+            "unreadable_metadata_is_an_error",
+            &[("ofl/broken", "name: \"Broken\"")],
+        );
+        // Not UTF-8, so it can't be read to a string
+        fs::write(root.join("ofl/broken/METADATA.pb"), b"name: \"\xff\"").unwrap();
+
+        let gf = GoogleFonts::new(root, None);
+        let [entry] = gf.families() else {
+            panic!("Expected one family");
+        };
+        assert!(matches!(entry.family, Err(GfError::Io { .. })));
+        assert_eq!(0, gf.ok_families().count());
+        assert_eq!(1, gf.parse_errors().count());
+        assert_eq!(
+            RepoStats {
+                families: 1,
+                failed: 1,
+                ..Default::default()
+            },
+            gf.stats()
+        );
+        assert!(gf.family_by_name("Broken").is_none());
+    }
+
+    #[test]
+    fn roboto_family_ext() {
+        let roboto = read_family(&testdata_file_content("roboto-metadata.pb")).unwrap();
+        assert_eq!(vec![400], roboto.weights());
+        assert!(roboto.has_italic());
+        assert!(roboto.is_variable());
+        assert_eq!(vec!["normal", "italic"], roboto.styles());
+        assert_eq!(Some((100.0, 900.0)), roboto.axis_range("wght"));
+        assert_eq!(None, roboto.axis_range("opsz"));
+        assert_eq!(Some(900.0), roboto.axis("wght").map(|a| a.max_value()));
+        assert_eq!(Some((100, 900)), roboto.weight_range());
+        assert_eq!(None, FamilyProto::new().weight_range());
+        assert_eq!(
+            vec![("wdth", 75.0, 100.0, 100.0), ("wght", 100.0, 400.0, 900.0)],
+            roboto.axes_summary()
+        );
+    }
+
+    #[test]
+    fn families_with_tag_strongest_first() {
+        let root = temp_repo_with_tags(
+            "families_with_tag_strongest_first",
+            &[(
+                "families.csv",
+                "Lora,/Expressive/Calm,40\nRoboto,/Expressive/Calm,90\nInter,/Expressive/Calm,50\nRoboto,/Quality/Drawing,80\n",
+            )],
+        );
+        let gf = GoogleFonts::new(root, None);
+        assert_eq!(
+            vec![("Roboto", 90.0), ("Inter", 50.0)],
+            gf.families_with_tag("/Expressive/Calm", Some(50.0))
+        );
+        assert_eq!(3, gf.families_with_tag("/Expressive/Calm", None).len());
+        assert!(gf.families_with_tag("/Expressive/Loud", None).is_empty());
+    }
+
+    #[test]
+    fn out_of_range_tags_reported() {
+        let root = temp_repo_with_tags(
+            "out_of_range_tags_reported",
+            &[(
+                "families.csv",
+                "Roboto,/Quality/Drawing,90\nLora,/Quality/Drawing,120\nInter,/Quality/Drawign,50\n",
+            )],
+        );
+        fs::write(
+            root.join("tags/tags_metadata.csv"),
+            "/Quality/Drawing,0,100,drawing quality\n",
+        )
+        .unwrap();
+        let gf = GoogleFonts::new(root, None);
+        let report: Vec<_> = gf
+            .out_of_range_tags()
+            .into_iter()
+            .map(|(t, m)| (t.family.as_str(), m.map(|m| m.tag.as_str())))
+            .collect();
+        assert_eq!(
+            vec![("Lora", Some("/Quality/Drawing")), ("Inter", None)],
+            report
+        );
+        assert_eq!(
+            Some("drawing quality"),
+            gf.tag_metadata_for("/Quality/Drawing")
+                .map(|m| m.prompt_name.as_str())
+        );
+    }
+
+    #[test]
+    fn designer_by_name() {
+        let root = temp_repo_with_tags("designer_by_name", &[]);
+        let designer_dir = root.join("catalog/designers/christianrobertson");
+        fs::create_dir_all(&designer_dir).unwrap();
+        fs::write(
+            designer_dir.join("info.pb"),
+            "designer: \"Christian Robertson\"\nlink: \"\"\n",
+        )
+        .unwrap();
+        let gf = GoogleFonts::new(root, None);
+        assert_eq!(1, gf.designers().len());
+        assert!(gf.designer("Christian Robertson").is_some());
+        assert!(gf.designer("Nobody").is_none());
+    }
+
+    #[test]
+    fn license_from_directory() {
+        let root = temp_repo_with_families(
+            "license_from_directory",
+            &[
+                ("apache/testsans", "name: \"Test Sans\" fonts {}"),
+                ("other/testserif", "name: \"Test Serif\" fonts {}"),
+            ],
+        );
+        let gf = GoogleFonts::new(root, None);
+        let license = |name| gf.license(gf.family_by_name(name).unwrap().1);
+        assert_eq!(Some(License::Apache), license("Test Sans"));
+        assert_eq!(Some(License::Unknown), license("Test Serif"));
+    }
+
+    #[test]
+    fn roboto_css_font_face() {
+        let roboto = read_family(&testdata_file_content("roboto-metadata.pb")).unwrap();
+        let css = css_font_face(&roboto, "https://example.com/fonts/");
+        assert_eq!(
+            "@font-face {\n  font-family: \"Roboto\";\n  font-style: italic;\n  font-weight: 100 900;\n  src: url(\"https://example.com/fonts/Roboto-Italic[wdth,wght].ttf\") format(\"truetype\") tech(variations);\n}\n",
+            css.split_inclusive("}\n").nth(1).unwrap()
+        );
+        assert_eq!(2, css.matches("@font-face").count());
+    }
+
+    #[test]
+    fn find_family_fuzzy_ranks_matches() {
+        let root = temp_repo_with_families(
+            "find_family_fuzzy_ranks_matches",
+            &[
+                ("ofl/roboto", "name: \"Roboto\" fonts {}"),
+                ("ofl/robotoslab", "name: \"Roboto Slab\" fonts {}"),
+                ("ofl/lora", "name: \"Lora\" fonts {}"),
+            ],
+        );
+        let gf = GoogleFonts::new(root, None);
+        fn names<'a>(gf: &'a GoogleFonts, query: &str) -> Vec<(&'a str, bool)> {
+            gf.find_family_fuzzy(query)
+                .into_iter()
+                .map(|(f, score)| (f.name(), score == 1.0))
+                .collect()
+        }
+        let roboto = names(&gf, " roboto");
+        assert!(roboto.len() >= 2);
+        assert_eq!(vec![("Roboto", true), ("Roboto Slab", false)], roboto[..2]);
+        assert_eq!(
+            Some(&("Roboto Slab", true)),
+            names(&gf, "robotoslab").first()
+        );
+        assert_eq!(Some(&("Roboto", false)), names(&gf, "Robto").first());
+    }
+
+    #[test]
+    fn axis_fallback_names() {
+        let axis: AxisProto = protobuf::text_format::parse_from_str(
+            r#"
+            tag: "wght"
+            min_value: 100
+            default_value: 400
+            max_value: 900
+            fallback { name: "Thin" value: 100 }
+            fallback { name: "Regular" value: 400 }
+            fallback { name: "Bold" value: 700 }
+            "#,
+        )
+        .unwrap();
+        assert_eq!(Some("Bold"), axis.fallback_name(700.0));
+        assert_eq!(None, axis.fallback_name(650.0));
+        assert_eq!(Some("Bold"), axis.nearest_fallback_name(650.0));
+        assert_eq!(Some("Thin"), axis.nearest_fallback_name(0.0));
+        assert_eq!(None, AxisProto::new().nearest_fallback_name(400.0));
+    }
+
+    #[test]
+    fn discover_repo_root() {
+        let root = temp_repo_with_families(
+            "discover_repo_root",
+            &[("ofl/testsans", "name: \"Test Sans\"")],
+        );
+        let family_dir = root.join("ofl/testsans");
+
+        let gf = GoogleFonts::discover(&family_dir, None).unwrap();
+        assert_eq!(root, gf.repo_dir);
+        assert!(GoogleFonts::discover(Path::new("/"), None).is_none());
+    }
+
+    #[test]
+    fn glob_family_filter() {
+        let re = glob_family_regex("ofl/rob[!x]to*");
+        assert!(re.is_match("/repo/ofl/roboto/METADATA.pb"));
+        assert!(re.is_match("/repo/ofl/robotoslab/METADATA.pb"));
+        assert!(!re.is_match("/repo/ofl/roboto/extra/METADATA.pb"));
+        assert!(!re.is_match("/repo/apache/roboto/METADATA.pb"));
+        assert!(glob_family_regex("**/lora").is_match("/repo/ofl/lora/METADATA.pb"));
+
+        let root = temp_repo_with_families(
+            "glob_family_filter",
+            &[
+                ("ofl/roboto", "name: \"Roboto\""),
+                ("ofl/robotoslab", "name: \"Roboto Slab\""),
+                ("ofl/lora", "name: \"Lora\""),
+            ],
+        );
+        let gf = GoogleFonts::with_glob_filter(root, "ofl/roboto*");
+        assert_eq!(2, gf.families().len());
+        assert_eq!(2, gf.ok_families().count());
+    }
+
+    #[test]
+    fn exemplar_for_style_requires_style() {
+        let roboto = read_family(&testdata_file_content("roboto-metadata.pb")).unwrap();
+        assert_eq!(
+            "Roboto-Italic[wdth,wght].ttf",
+            exemplar_for_style(&roboto, FontStyle::Italic)
+                .unwrap()
+                .filename()
+        );
+        let kosugi = read_family(&testdata_file_content("kosugimaru-metadata.pb")).unwrap();
+        assert!(exemplar_for_style(&kosugi, FontStyle::Italic).is_none());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn roboto_to_json() {
+        let roboto = read_family(&testdata_file_content("roboto-metadata.pb")).unwrap();
+        let json = family_to_json(&roboto);
+        assert_eq!("Roboto", json["name"]);
+        assert_eq!("Roboto[wdth,wght].ttf", json["fonts"][0]["filename"]);
+        assert_eq!(json, serde_json::to_value(&roboto).unwrap());
+    }
+
+    #[test]
+    fn css2_urls() {
+        let roboto = read_family(&testdata_file_content("roboto-metadata.pb")).unwrap();
+        assert_eq!(
+            "https://fonts.googleapis.com/css2?family=Roboto:ital,wght@0,100..900;1,100..900",
+            css2_url(&roboto)
+        );
+        let family = test_family(
+            r#"
+            name: "Test Sans"
+            fonts { style: "normal" weight: 700 filename: "TestSans-Bold.ttf" }
+            fonts { style: "normal" weight: 400 filename: "TestSans-Regular.ttf" }
+            "#,
+        );
+        assert_eq!(
+            "https://fonts.googleapis.com/css2?family=Test+Sans:wght@400;700",
+            css2_url(&family)
+        );
+    }
+
+    #[test]
+    fn language_base_chars_splits_clusters() {
+        let lang = LanguageProto {
+            exemplar_chars: Some(ExemplarCharsProto {
+                base: Some("a b {ch} ñ".to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        assert_eq!(vec!['a', 'b', 'c', 'h', 'ñ'], language_base_chars(&lang));
+        assert!(language_base_chars(&LanguageProto::default()).is_empty());
+    }
+
+    #[test]
+    fn reload_sees_new_families() {
+        let root = temp_repo_with_tags("reload_sees_new_families", &[]);
+        let hour = std::time::Duration::from_secs(3600);
+        // Stamp each family explicitly rather than relying on the clock and
+        // the filesystem's timestamp granularity
+        let add_family = |dir: &str, modified: SystemTime| {
+            write_families(&root, &[(dir, "name: \"X\"")]);
+            File::options()
+                .write(true)
+                .open(root.join(dir).join("METADATA.pb"))
+                .unwrap()
+                .set_modified(modified)
+                .unwrap();
+        };
+        add_family("ofl/one", SystemTime::now() - hour);
+        let mut gf = GoogleFonts::new(root.clone(), None);
+        assert!(!gf.reload_if_changed());
+        assert_eq!(1, gf.families().len());
+        assert!(!gf.reload_if_changed());
+
+        add_family("ofl/two", SystemTime::now() + hour);
+        assert_eq!(1, gf.families().len());
+        assert!(gf.reload_if_changed());
+        assert_eq!(2, gf.families().len());
+
+        add_family("ofl/three", SystemTime::now());
+        gf.reload();
+        assert_eq!(3, gf.families().len());
+    }
+
+    #[test]
+    fn read_family_from_reader_and_bytes() {
+        let content = testdata_file_content("wixmadefortext-metadata.pb");
+        let from_str = read_family(&content).unwrap();
+        let from_reader = read_family_from_reader(content.as_bytes())
+            .unwrap()
+            .unwrap();
+        let from_bytes = read_family_bytes(content.as_bytes()).unwrap().unwrap();
+        assert_eq!(from_str, from_reader);
+        assert_eq!(from_str, from_bytes);
+        assert_eq!(
+            ErrorKind::InvalidData,
+            read_family_bytes(b"name: \"\xff\"").unwrap_err().kind()
+        );
+    }
+
+    #[test]
+    fn family_diff_matches_fonts_by_filename() {
+        let old = read_family(&testdata_file_content("roboto-metadata.pb")).unwrap();
+        let mut new = old.clone();
+        assert!(family_diff(&old, &new).is_empty());
+
+        new.fonts.reverse();
+        assert!(family_diff(&old, &new).is_empty());
+
+        new.fonts[0].set_weight(500);
+        new.fonts.pop();
+        new.subsets.push("khmer".to_string());
+        new.axes.retain(|a| a.tag() != "wdth");
+        assert_eq!(
+            vec![
+                FamilyChange::FontRemoved("Roboto[wdth,wght].ttf".to_string()),
+                FamilyChange::WeightChanged {
+                    filename: "Roboto-Italic[wdth,wght].ttf".to_string(),
+                    old: 400,
+                    new: 500
+                },
+                FamilyChange::SubsetAdded("khmer".to_string()),
+                FamilyChange::AxisRemoved("wdth".to_string()),
+            ],
+            family_diff(&old, &new)
+        );
+    }
+
+    #[test]
+    fn scripts_in_repo() {
+        let root = temp_repo_with_families(
+            "scripts_in_repo",
+            &[
+                ("ofl/a", "primary_script: \"Jpan\""),
+                ("ofl/b", ""),
+                ("ofl/c", "primary_script: \"Deva\""),
+                ("ofl/d", "primary_script: \"Jpan\""),
+            ],
+        );
+        let gf = GoogleFonts::new(root, None);
+        assert_eq!(4, gf.ok_families().count());
+        assert_eq!(vec!["Deva", "Jpan"], gf.scripts());
+        assert_eq!(2, gf.families_for_script("Jpan").len());
+        assert!(gf.families_for_script("Latn").is_empty());
+    }
+
+    #[test]
+    fn kosugi_sample_text_is_japanese() {
+        let gf = GoogleFonts::new(PathBuf::from("/nonexistent"), None);
+        let kosugi = read_family(&testdata_file_content("kosugimaru-metadata.pb")).unwrap();
+        let lang = gf.primary_language(&kosugi);
+        assert_eq!(language_sample_text(lang), gf.sample_text(&kosugi));
+        assert!(gf.sample_text(&kosugi).is_some());
+    }
+
+    #[test]
+    fn families_supporting_hindi() {
+        let root = temp_repo_with_families(
+            "families_supporting_hindi",
+            &[
+                ("ofl/listed", "name: \"Listed\" languages: \"hi_Deva\""),
+                (
+                    "ofl/primary",
+                    "name: \"Primary\" primary_language: \"hi_Deva\"",
+                ),
+                ("ofl/script", "name: \"Script\" primary_script: \"Deva\""),
+                ("ofl/latin", "name: \"Latin\" languages: \"en_Latn\""),
+            ],
+        );
+        let gf = GoogleFonts::new(root, None);
+        let mut names: Vec<_> = gf
+            .families_supporting_language("hi_Deva")
+            .into_iter()
+            .map(|f| f.name())
+            .collect();
+        names.sort();
+        assert_eq!(vec!["Listed", "Primary", "Script"], names);
+    }
+
+    #[test]
+    fn roboto_subsets() {
+        let roboto = read_family(&testdata_file_content("roboto-metadata.pb")).unwrap();
+        assert!(roboto.has_subset("latin-ext"));
+        assert!(!roboto.has_subset("khmer"));
+        assert_eq!(Some(&"cyrillic"), roboto.subsets_sorted().first());
+        let subsets = roboto.typed_subsets();
+        assert_eq!(Subset::Cyrillic, subsets[0]);
+        assert_eq!(Subset::Vietnamese, subsets[subsets.len() - 1]);
+        assert_eq!(Some("Latn"), Subset::LatinExt.script());
+        assert_eq!(
+            Subset::Other("klingon".to_string()),
+            "klingon".parse().unwrap()
+        );
+        assert_eq!("latin-ext", Subset::LatinExt.to_string());
+    }
+
+    #[test]
+    fn iter_tags_is_lazy_past_errors() {
+        let root = temp_repo_with_tags(
+            "iter_tags_is_lazy_past_errors",
+            &[(
+                "families.csv",
+                "Roboto,/Quality/Drawing,90\nRoboto,/Quality/Drawing,nope\nLora,/Expressive/Calm,40\n",
+            )],
+        );
+        let results: Vec<_> = iter_tags(&root).collect();
+        assert_eq!(3, results.len());
+        assert!(results[1].is_err());
+        assert_eq!("Lora", results[2].as_ref().unwrap().family);
+        assert!(
+            iter_tags(Path::new("/nonexistent"))
+                .next()
+                .unwrap()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn read_tags_handles_bom_and_crlf() {
+        let root = temp_repo_with_tags(
+            "read_tags_handles_bom_and_crlf",
+            &[(
+                "families.csv",
+                "\u{feff}Roboto,/Quality/Drawing,90\r\nLora,/Expressive/Calm,40\r\n",
+            )],
+        );
+        let tags = read_tags(&root).unwrap();
+        assert_eq!(
+            vec![
+                ("Roboto", "/Quality/Drawing", 90.0),
+                ("Lora", "/Expressive/Calm", 40.0)
+            ],
+            tags.iter()
+                .map(|t| (t.family.as_str(), t.tag.as_str(), t.value))
+                .collect::<Vec<_>>()
+        );
+        fs::write(
+            root.join("tags/tags_metadata.csv"),
+            "\u{feff}/Quality/Drawing,0,100,drawing quality\r\n",
+        )
+        .unwrap();
+        let metadata = read_tag_metadata(&root).unwrap();
+        assert_eq!("/Quality/Drawing", metadata[0].tag);
+        assert_eq!("drawing quality", metadata[0].prompt_name);
+    }
+
+    #[test]
+    fn default_instance_prefers_static_regular() {
+        let roboto = read_family(&testdata_file_content("roboto-metadata.pb")).unwrap();
+        assert_eq!(
+            "Roboto[wdth,wght].ttf",
+            default_instance(&roboto).unwrap().filename()
+        );
+
+        let mut mixed = roboto.clone();
+        let mut regular = mixed.fonts[0].clone();
+        regular.set_filename("Roboto-Regular.ttf".to_string());
+        mixed.fonts.push(regular);
+        assert_eq!(
+            "Roboto-Regular.ttf",
+            default_instance(&mixed).unwrap().filename()
+        );
+
+        let mut heavy = roboto.clone();
+        heavy.axes.iter_mut().for_each(|a| a.set_min_value(600.0));
+        assert!(default_instance(&heavy).is_none());
+    }
+
+    #[test]
+    fn filename_conventions() {
+        let roboto = read_family(&testdata_file_content("roboto-metadata.pb")).unwrap();
+        assert!(filename_convention_offenders(&roboto).is_empty());
+
+        let family = test_family(
+            r#"
+            name: "Roboto Slab"
+            fonts { filename: "RobotoSlab[wght].ttf" }
+            fonts { filename: "RobotoSlab-Bold.ttf" }
+            fonts { filename: "Roboto Slab-Bold.ttf" }
+            fonts { filename: "RobotoSlabBold.ttf" }
+            "#,
+        );
+        let offenders: Vec<_> = filename_convention_offenders(&family)
+            .into_iter()
+            .map(|f| f.filename())
+            .collect();
+        assert_eq!(
+            vec!["Roboto Slab-Bold.ttf", "RobotoSlabBold.ttf"],
+            offenders
+        );
+    }
+
+    #[test]
+    fn roboto_best_per_style() {
+        let roboto = read_family(&testdata_file_content("roboto-metadata.pb")).unwrap();
+        let mut family = roboto.clone();
+        let mut bold = family.fonts[0].clone();
+        bold.set_filename("Roboto-Bold.ttf".to_string());
+        bold.set_weight(700);
+        family.fonts.insert(0, bold);
+        let best: Vec<_> = best_per_style(&family)
+            .into_iter()
+            .map(|f| f.filename())
+            .collect();
+        assert_eq!(
+            vec!["Roboto[wdth,wght].ttf", "Roboto-Italic[wdth,wght].ttf"],
+            best
+        );
+    }
+
+    #[test]
+    fn roboto_font_for() {
+        let roboto = read_family(&testdata_file_content("roboto-metadata.pb")).unwrap();
+        assert_eq!(
+            Some("Roboto-Italic[wdth,wght].ttf"),
+            roboto
+                .font_for(FontStyle::Italic, 650)
+                .map(|f| f.filename())
+        );
+        assert!(roboto.has_font(FontStyle::Normal, 100));
+        assert!(!roboto.has_font(FontStyle::Normal, 950));
+    }
+
+    #[test]
+    fn family_description_prefers_article() {
+        let root = temp_repo_with_families(
+            "family_description_prefers_article",
+            &[("ofl/testsans", "name: \"Test Sans\" fonts {}")],
+        );
+        let family_dir = root.join("ofl/testsans");
+        fs::create_dir_all(family_dir.join("article")).unwrap();
+        fs::write(family_dir.join("DESCRIPTION.en.html"), "<p>Short</p>").unwrap();
+        let gf = GoogleFonts::new(root, None);
+        let (_, family) = gf.family_by_name("Test Sans").unwrap();
+        assert_eq!(
+            Some("<p>Short</p>".to_string()),
+            gf.family_description(family)
+        );
+
+        fs::write(family_dir.join("article/ARTICLE.en.html"), "<p>Long</p>").unwrap();
+        assert_eq!(
+            Some("<p>Long</p>".to_string()),
+            gf.family_description(family)
+        );
+    }
+
+    #[test]
+    fn family_designers_skip_unknown_names() {
+        let root = temp_repo_with_tags("family_designers_skip_unknown_names", &[]);
+        let designer_dir = root.join("catalog/designers/christianrobertson");
+        fs::create_dir_all(&designer_dir).unwrap();
+        fs::write(
+            designer_dir.join("info.pb"),
+            "designer: \"Christian Robertson\"\nlink: \"\"\n",
+        )
+        .unwrap();
+        let gf = GoogleFonts::new(root, None);
+        let mut family = FamilyProto::new();
+        family.set_designer("Nobody, Christian Robertson,".to_string());
+        assert_eq!(
+            vec!["Nobody".to_string(), "Christian Robertson".to_string()],
+            family.designer_names()
+        );
+        let designers = gf.family_designers(&family);
+        assert_eq!(1, designers.len());
+        assert_eq!("Christian Robertson", designers[0].designer());
+    }
+
+    #[test]
+    fn language_lookup_is_tolerant() {
+        let gf = GoogleFonts::new(PathBuf::from("/nonexistent"), None);
+        let en = gf.language_exact("en_Latn").unwrap();
+        assert!(gf.language_exact("en-latn").is_none());
+        assert_eq!(Some(en), gf.language("en-latn"));
+        assert_eq!(Some(en), gf.language("EN_LATN"));
+        assert_eq!(Some(en), gf.language_by_prefix("en"));
+        assert!(gf.language_by_prefix("zzz").is_none());
+    }
+
+    #[test]
+    fn binary_size_skips_missing_fonts() {
+        let root = temp_repo_with_families(
+            "binary_size_skips_missing_fonts",
+            &[(
+                "ofl/testsans",
+                r#"
+                name: "Test Sans"
+                fonts { style: "normal" weight: 400 filename: "TestSans-Regular.ttf" }
+                fonts { style: "italic" weight: 400 filename: "TestSans-Italic.ttf" }
+                "#,
+            )],
+        );
+        let family_dir = root.join("ofl/testsans");
+        fs::write(family_dir.join("TestSans-Regular.ttf"), [0u8; 12]).unwrap();
+
+        let gf = GoogleFonts::new(root, None);
+        let family = gf.families()[0].family().unwrap();
+        assert_eq!(12, gf.family_binary_size(family).unwrap());
+        assert_eq!(12, gf.total_binary_size().unwrap());
+    }
+
+    #[test]
+    fn weight_names() {
+        assert_eq!(Some(700), weight_name_to_number("Bold"));
+        assert_eq!(Some(200), weight_name_to_number("extra-light"));
+        assert_eq!(Some(600), weight_name_to_number("Semi Bold"));
+        assert_eq!(Some(400), weight_name_to_number("normal"));
+        assert_eq!(None, weight_name_to_number("chunky"));
+
+        let roboto = read_family(&testdata_file_content("roboto-metadata.pb")).unwrap();
+        assert_eq!(
+            Some("Roboto-Italic[wdth,wght].ttf"),
+            select_font_by_weight_name(&roboto, FontStyle::Italic, "bold").map(|f| f.filename())
+        );
+        assert!(select_font_by_weight_name(&roboto, FontStyle::Normal, "chunky").is_none());
+    }
+
+    #[test]
+    fn families_by_name_skips_unparsed() {
+        let root = temp_repo_with_families(
+            "families_by_name_skips_unparsed",
+            &[
+                ("ofl/testsans", "name: \"Test Sans\""),
+                ("ofl/broken", "name: \"Broken\" fonts { weight: \"heavy\" }"),
+            ],
+        );
+        let gf = GoogleFonts::new(root, None);
+        assert_eq!(1, gf.parse_errors().count());
+        let by_name: HashMap<_, _> = gf.families_by_name().collect();
+        assert_eq!(
+            vec!["Test Sans"],
+            by_name.keys().copied().collect::<Vec<_>>()
+        );
+        assert_eq!("Test Sans", by_name["Test Sans"].name());
+    }
+
+    #[test]
+    fn duplicate_font_files_resolve_deterministically() {
+        let root = temp_repo_with_families(
+            "duplicate_font_files_resolve_deterministically",
+            &[
+                (
+                    "ofl/b",
+                    "name: \"Second\" fonts { filename: \"Shared-Regular.ttf\" }",
+                ),
+                (
+                    "ofl/a",
+                    "name: \"First\" fonts { filename: \"Shared-Regular.ttf\" }",
+                ),
+            ],
+        );
+        let gf = GoogleFonts::new(root.clone(), None);
+        let a = root.join("ofl/a/METADATA.pb");
+        let b = root.join("ofl/b/METADATA.pb");
+        assert_eq!(
+            vec![("Shared-Regular.ttf", vec![a.as_path(), b.as_path()])],
+            gf.duplicate_font_files()
+        );
+        let mut font = FontProto::new();
+        font.set_filename("Shared-Regular.ttf".to_string());
+        assert_eq!(Some("First"), gf.family(&font).map(|(_, f)| f.name()));
+    }
+
+    #[test]
+    fn css_font_face_unicode_range() {
+        assert_eq!(Some("U+1F00-1FFF"), subset_unicode_range("greek-ext"));
+        assert_eq!(None, subset_unicode_range("japanese"));
+
+        let mut family = test_family(
+            r#"
+            name: "Test Sans"
+            subsets: "menu"
+            subsets: "greek"
+            subsets: "greek-ext"
+            fonts { style: "normal" weight: 400 filename: "TestSans-Regular.ttf" }
+            "#,
+        );
+        assert!(css_font_face(&family, "").contains(
+            "  unicode-range: U+0370-0377, U+037A-037F, U+0384-038A, U+038C, U+038E-03A1, U+03A3-03FF, U+1F00-1FFF;\n"
+        ));
+        family.subsets.push("japanese".to_string());
+        assert!(!css_font_face(&family, "").contains("unicode-range"));
+    }
+
+    #[test]
+    fn tag_statistics_from_taggings() {
+        let root = temp_repo_with_tags(
+            "tag_statistics_from_taggings",
+            &[(
+                "families.csv",
+                "Lora,/Expressive/Calm,40\nRoboto,/Expressive/Calm,90\nInter,/Expressive/Calm,50\nRoboto,/Quality/Drawing,80\n",
+            )],
+        );
+        let gf = GoogleFonts::new(root, None);
+        assert_eq!(
+            vec![
+                TagStat {
+                    tag: "/Expressive/Calm".to_string(),
+                    count: 3,
+                    observed_min: 40.0,
+                    observed_max: 90.0,
+                    mean: 60.0,
+                },
+                TagStat {
+                    tag: "/Quality/Drawing".to_string(),
+                    count: 1,
+                    observed_min: 80.0,
+                    observed_max: 80.0,
+                    mean: 80.0,
+                },
+            ],
+            gf.tag_statistics()
+        );
+    }
+
+    #[test]
+    fn exemplar_ties_ignore_font_order() {
+        let a = "fonts { style: \"normal\" weight: 400 filename: \"TestSans-A.ttf\" }\n";
+        let b = "fonts { style: \"normal\" weight: 400 filename: \"TestSans-B.ttf\" }\n";
+        for metadata in [format!("{a}{b}"), format!("{b}{a}")] {
+            let family = test_family(&metadata);
+            assert_eq!(
+                Some("TestSans-A.ttf"),
+                exemplar(&family).map(|f| f.filename())
+            );
+            assert_eq!(
+                Some("TestSans-A.ttf"),
+                select_font(&family, FontStyle::Normal, 400).map(|f| f.filename())
+            );
+            assert_eq!(
+                vec!["TestSans-A.ttf"],
+                best_per_style(&family)
+                    .iter()
+                    .map(|f| f.filename())
+                    .collect::<Vec<_>>()
+            );
+        }
+    }
+
+    #[test]
+    fn find_family_walks_lazily() {
+        let root = temp_repo_with_families(
+            "find_family_walks_lazily",
+            &[
+                ("ofl/testsans", "name: \"Test Sans\""),
+                ("ofl/testserif", "name: \"Test Serif\""),
+            ],
+        );
+        let gf = GoogleFonts::new(root.clone(), None);
+        let is_serif = |f: &FamilyProto| f.name() == "Test Serif";
+        let (path, family) = gf.find_family(is_serif).unwrap();
+        assert_eq!(root.join("ofl/testserif/METADATA.pb"), path);
+        assert_eq!("Test Serif", family.name());
+        assert!(gf.families.get().is_none());
+
+        gf.families();
+        assert_eq!(Some(family), gf.find_family(is_serif).map(|(_, f)| f));
+        assert!(gf.find_family(|f| f.name() == "Lora").is_none());
+    }
+
+    #[test]
+    fn primary_language_prefers_region() {
+        let gf = GoogleFonts::new(PathBuf::from("/nonexistent"), None);
+        let cyrillic = test_family("name: \"Test Sans\" primary_script: \"Cyrl\"");
+        let lang = gf.primary_language_for_region(&cyrillic, "bg");
+        assert_eq!("Cyrl", lang.script());
+        assert!(lang.region.iter().any(|r| r == "BG"));
+        assert_eq!(
+            gf.primary_language(&cyrillic),
+            gf.primary_language_for_region(&cyrillic, "nowhere")
+        );
+
+        let declared = test_family(
+            "name: \"Test Sans\" primary_script: \"Cyrl\" primary_language: \"ru_Cyrl\"",
+        );
+        assert_eq!(
+            "ru_Cyrl",
+            gf.primary_language_for_region(&declared, "BG").id()
+        );
+
+        let invalid = test_family(
+            "name: \"Test Sans\" primary_script: \"Cyrl\" primary_language: \"Invalid\"",
+        );
+        assert_eq!(
+            LanguageReason::InvalidDeclaredLanguage,
+            gf.primary_language_with_reason(&invalid).1
+        );
+        assert_eq!(lang, gf.primary_language_for_region(&invalid, "bg"));
+    }
+
+    #[test]
+    fn families_sorted_by_date_added() {
+        let root = temp_repo_with_families(
+            "families_sorted_by_date_added",
+            &[
+                ("ofl/b", "name: \"Beta\" date_added: \"2019-05-01\""),
+                ("ofl/a", "name: \"alpha\" date_added: \"2021-11-30\""),
+                (
+                    "ofl/c",
+                    "name: \"Gamma\" display_name: \"Aardvark\" date_added: \"\"",
+                ),
+                ("ofl/d", "name: \"Delta\" date_added: \"someday\""),
+            ],
+        );
+        let gf = GoogleFonts::new(root, None);
+        let names = |key| {
+            gf.families_sorted_by(key)
+                .into_iter()
+                .map(|f| f.name())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            vec!["Gamma", "alpha", "Beta", "Delta"],
+            names(SortKey::Name)
+        );
+        assert_eq!(
+            vec!["alpha", "Beta", "Gamma", "Delta"],
+            names(SortKey::DateAdded)
+        );
+    }
+
+    #[test]
+    fn variability_changes_between_checkouts() {
+        let write = |test_name: &str, families: &[(&str, &str)]| {
+            GoogleFonts::new(temp_repo_with_families(test_name, families), None)
+        };
+        let static_sans = "name: \"Sans\"\nfonts { filename: \"Sans-Regular.ttf\" }\n";
+        let variable_sans = "name: \"Sans\"\nfonts { filename: \"Sans[wght].ttf\" }\naxes { tag: \"wght\" min_value: 100 max_value: 900 }\n";
+        let variable_serif = "name: \"Serif\"\nfonts { filename: \"Serif[wght].ttf\" }\naxes { tag: \"wght\" min_value: 400 max_value: 700 }\n";
+        let wider_serif = "name: \"Serif\"\nfonts { filename: \"Serif[wght].ttf\" }\naxes { tag: \"wght\" min_value: 100 max_value: 900 }\n";
+        let old = write(
+            "variability_changes_old",
+            &[("ofl/sans", static_sans), ("ofl/serif", variable_serif)],
+        );
+        let new = write(
+            "variability_changes_new",
+            &[("ofl/sans", variable_sans), ("ofl/serif", wider_serif)],
+        );
+        assert!(became_variable(
+            &test_family(static_sans),
+            &test_family(variable_sans)
+        ));
+        assert_eq!(
+            vec![
+                ("Sans", VariabilityChange::StaticToVariable),
+                ("Serif", VariabilityChange::AxesChanged),
+            ],
+            old.variability_changes(&new)
+        );
+        assert_eq!(
+            vec![
+                ("Sans", VariabilityChange::VariableToStatic),
+                ("Serif", VariabilityChange::AxesChanged),
+            ],
+            new.variability_changes(&old)
+        );
+    }
+
+    #[test]
+    fn menu_font_falls_back_to_exemplar() {
+        let roboto = read_family(&testdata_file_content("roboto-metadata.pb")).unwrap();
+        assert!(roboto.has_subset("menu"));
+        assert_eq!(default_instance(&roboto), menu_font(&roboto));
+
+        let mut heavy = roboto.clone();
+        heavy.axes.iter_mut().for_each(|a| a.set_min_value(600.0));
+        assert!(default_instance(&heavy).is_none());
+        assert_eq!(
+            Some("Roboto[wdth,wght].ttf"),
+            menu_font(&heavy).map(|f| f.filename())
+        );
+    }
+
+    #[test]
+    fn read_tags_skips_header_row() {
+        let root = temp_repo_with_tags(
+            "read_tags_skips_header_row",
+            &[(
+                "families.csv",
+                "Family,Group/Tag,Weight\nRoboto,/Quality/Drawing,90\nLora,,/Expressive/Calm,40\n",
+            )],
+        );
+        let tags = read_tags(&root).unwrap();
+        assert_eq!(
+            vec!["Roboto", "Lora"],
+            tags.iter().map(|t| t.family.as_str()).collect::<Vec<_>>()
+        );
+        assert_eq!(2, iter_tags(&root).filter(|t| t.is_ok()).count());
+    }
+
+    #[test]
+    fn read_tags_skips_comments() {
+        let root = temp_repo_with_tags(
+            "read_tags_skips_comments",
+            &[(
+                "families.csv",
+                "# Drawing quality\nRoboto,/Quality/Drawing,90\n\n  # Calm\nLora,/Expressive/Calm,40\n",
+            )],
+        );
+        let tags = read_tags(&root).unwrap();
+        assert_eq!(
+            vec!["Roboto", "Lora"],
+            tags.iter().map(|t| t.family.as_str()).collect::<Vec<_>>()
+        );
+        assert!(Tagging::from_str("# Drawing quality").is_err());
+    }
+
+    #[test]
+    fn category_conflicts_with_tags() {
+        let root = temp_repo_with_tags(
+            "category_conflicts_with_tags",
+            &[(
+                "families.csv",
+                "Test Sans,/Serif/Humanist,80\nTest Sans,/Sans/Grotesque,20\n\
+                 Test Serif,/Slab/Geometric,90\nTest Mono,/Sans/Geometric,30\n",
+            )],
+        );
+        write_families(
+            &root,
+            &[
+                (
+                    "ofl/testsans",
+                    "name: \"Test Sans\" category: \"SANS_SERIF\"",
+                ),
+                ("ofl/testserif", "name: \"Test Serif\" category: \"SERIF\""),
+                (
+                    "ofl/testmono",
+                    "name: \"Test Mono\" category: \"MONOSPACE\"",
+                ),
+            ],
+        );
+        let gf = GoogleFonts::new(root, None);
+        let conflicts = gf.category_tag_conflicts();
+        assert_eq!(
+            vec![(
+                "Test Sans",
+                "category SANS_SERIF but strongest classification tag is /Serif/ (80)".to_string()
+            )],
+            conflicts
+                .into_iter()
+                .map(|(f, reason)| (f.name(), reason))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn copyright_holders() {
+        let roboto = read_family(&testdata_file_content("roboto-metadata.pb")).unwrap();
+        assert_eq!(
+            Some("The Roboto Project Authors"),
+            roboto.fonts[0].copyright_holder()
+        );
+        assert_eq!(
+            Some("The Roboto Project Authors"),
+            roboto.copyright_holder()
+        );
+
+        let mut font = FontProto::new();
+        for (copyright, holder) in [
+            (
+                "Copyright (c) 2010-2015, Jane Doe <jane@example.com>, with Reserved Font Name \"Sans\"",
+                Some("Jane Doe"),
+            ),
+            ("© 2019 by Jane Doe. All rights reserved.", Some("Jane Doe")),
+            ("Copyright 2020", None),
+        ] {
+            font.set_copyright(copyright.to_string());
+            assert_eq!(holder, font.copyright_holder(), "{copyright}");
+        }
+    }
+
+    #[test]
+    fn tags_for_family_grouped_by_location() {
+        let root = temp_repo_with_tags(
+            "tags_for_family_grouped_by_location",
+            &[(
+                "families.csv",
+                "Roboto,/Quality/Drawing,80\nRoboto,wght@700,/Expressive/Loud,70\nLora,/Expressive/Calm,40\nRoboto,/Expressive/Calm,90\n",
+            )],
+        );
+        let gf = GoogleFonts::new(root, None);
+        assert_eq!(
+            vec!["/Quality/Drawing", "/Expressive/Loud", "/Expressive/Calm"],
+            gf.tags_for_family("Roboto")
+                .iter()
+                .map(|t| t.tag.as_str())
+                .collect::<Vec<_>>()
+        );
+        let grouped = gf.tags_for_family_grouped("Roboto");
+        assert_eq!(2, grouped[""].len());
+        assert_eq!("/Expressive/Loud", grouped["wght@700"][0].tag);
+        assert!(gf.tags_for_family("Inter").is_empty());
+    }
+
+    #[test]
+    fn read_family_renames_legacy_fields() {
+        let family = read_family(
+            r#"
+            name: "Test Sans"
+            designer: "Test Designer"
+            license: "OFL"
+            dateAdded: "2020-01-01"
+            fonts {
+              name: "Test Sans"
+              style: "normal"
+              weight: 400
+              filename: "TestSans-Regular.ttf"
+              postScriptName: "TestSans-Regular"
+              # fullName: "kept as a comment"
+              fullName: "Test Sans fullName: Regular"
+            }
+            axes { tag: "wght" minValue: 100 maxValue: 900 }
+            "#,
+        )
+        .unwrap();
+        assert_eq!("2020-01-01", family.date_added());
+        assert_eq!("TestSans-Regular", family.fonts[0].post_script_name());
+        assert_eq!("Test Sans fullName: Regular", family.fonts[0].full_name());
+        assert_eq!(Some((100.0, 900.0)), family.axis_range("wght"));
+
+        let content = testdata_file_content("roboto-metadata.pb");
+        assert!(matches!(rename_legacy_fields(&content), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn families_by_category() {
+        let root = temp_repo_with_families(
+            "families_by_category",
+            &[
+                ("ofl/sans", "name: \"ofl/sans\" category: \"SANS_SERIF\""),
+                ("ofl/serif", "name: \"ofl/serif\" category: \"SERIF\""),
+                ("ofl/odd", "name: \"ofl/odd\" category: \"BLACKLETTER\""),
+            ],
+        );
+        let gf = GoogleFonts::new(root, None);
+        let names = |category| {
+            gf.families_in_category(category)
+                .into_iter()
+                .map(|f| f.name())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(vec!["ofl/serif"], names(Category::Serif));
+        assert_eq!(
+            vec!["ofl/odd"],
+            names(Category::Other("BLACKLETTER".to_string()))
+        );
+        assert!(names(Category::Monospace).is_empty());
+        assert_eq!(Category::SansSerif, "Sans Serif".parse().unwrap());
+        assert_eq!("SANS_SERIF", Category::SansSerif.to_string());
+    }
+
+    #[test]
+    fn select_font_at_most_respects_cap() {
+        let family = test_family(
+            r#"
+            fonts { style: "normal" weight: 300 filename: "TestSans-Light.ttf" }
+            fonts { style: "normal" weight: 700 filename: "TestSans-Bold.ttf" }
+            fonts { style: "italic" weight: 600 filename: "TestSans-SemiBoldItalic.ttf" }
+            "#,
+        );
+        let at_most =
+            |style, weight| select_font_at_most(&family, style, weight).map(|f| f.filename());
+        assert_eq!(Some("TestSans-Light.ttf"), at_most(FontStyle::Normal, 600));
+        assert_eq!(Some("TestSans-Bold.ttf"), at_most(FontStyle::Normal, 900));
+        assert_eq!(None, at_most(FontStyle::Normal, 200));
+        assert_eq!(None, at_most(FontStyle::Italic, 500));
+
+        let mut roboto = read_family(&testdata_file_content("roboto-metadata.pb")).unwrap();
+        assert_eq!(
+            Some("Roboto[wdth,wght].ttf"),
+            select_font_at_most(&roboto, FontStyle::Normal, 450).map(|f| f.filename())
+        );
+        roboto.axes.iter_mut().for_each(|a| a.set_min_value(500.0));
+        assert!(select_font_at_most(&roboto, FontStyle::Normal, 450).is_none());
+    }
+
+    #[test]
+    fn content_hash_ignores_layout() {
+        let a = read_family(
+            r#"
+            name: "Test Sans"
+            designer: "Test Designer"
+            license: "OFL"
+            date_added: "2020-01-01"
+            subsets: "latin"
+            fonts {
+              name: "Test Sans"
+              style: "normal"
+              weight: 400
+              filename: "TestSans-Regular.ttf"
+              post_script_name: "TestSans-Regular"
+              full_name: "Test Sans Regular"
+            }
+            "#,
+        )
+        .unwrap();
+        let b = read_family(
+            "fonts { full_name: \"Test Sans Regular\" post_script_name: \"TestSans-Regular\"\n\
+             filename: \"TestSans-Regular.ttf\" weight: 400 style: \"normal\" name: \"Test Sans\" }\n\
+             subsets: \"latin\" date_added: \"2020-01-01\" license: \"OFL\"\n\
+             designer: \"Test Designer\" name: \"Test Sans\"",
+        )
+        .unwrap();
+        assert_eq!(a.content_hash(), b.content_hash());
+        assert_eq!(0xcbf2_9ce4_8422_2325, FamilyProto::new().content_hash());
+
+        let mut c = a.clone();
+        c.fonts[0].set_weight(700);
+        assert_ne!(a.content_hash(), c.content_hash());
+    }
+
+    #[test]
+    fn content_hash_with_map_entries() {
+        let metadata = complete_metadata(
+            r#"
+            name: "Roboto"
+            registry_default_overrides { key: "wght" value: 400 }
+            registry_default_overrides { key: "GRAD" value: 0 }
+            registry_default_overrides { key: "opsz" value: 14 }
+            registry_default_overrides { key: "wdth" value: 100 }
+            registry_default_overrides { key: "XTRA" value: 468 }
+            "#,
+        );
+        for _ in 0..10 {
+            let family = read_family(&metadata).unwrap();
+            assert_eq!(0xa730_2bbe_bf60_e871, family.content_hash());
+        }
+    }
+
+    #[test]
+    fn metadata_paths_without_parsing() {
+        let root = temp_repo_with_families(
+            "metadata_paths_without_parsing",
+            &[
+                ("ofl/testsans", "not { valid"),
+                ("apache/testserif", "not { valid"),
+            ],
+        );
+        let gf = GoogleFonts::builder()
+            .repo(root.clone())
+            .family_filter(Regex::new("ofl/").unwrap())
+            .build();
+        assert_eq!(
+            vec![root.join("ofl/testsans/METADATA.pb")],
+            gf.metadata_paths()
+        );
+        assert!(gf.families.get().is_none());
+    }
+
+    #[test]
+    fn in_memory_families() {
+        let roboto = read_family(&testdata_file_content("roboto-metadata.pb")).unwrap();
+        let kosugi = read_family(&testdata_file_content("kosugimaru-metadata.pb")).unwrap();
+        let gf = GoogleFonts::from_families(vec![
+            (PathBuf::from("ofl/roboto/METADATA.pb"), roboto.clone()),
+            (PathBuf::from("ofl/kosugimaru/METADATA.pb"), kosugi),
+        ]);
+        assert_eq!(2, gf.families().len());
+        let (path, family) = gf.family_by_name("Roboto").unwrap();
+        assert_eq!(Path::new("ofl/roboto/METADATA.pb"), path);
+        let font = exemplar(family).unwrap();
+        assert_eq!(Some("Roboto"), gf.family(font).map(|(_, f)| f.name()));
+        assert!(gf.find_font_binary(font).is_none());
+        let (_, kosugi) = gf.family_by_name("Kosugi Maru").unwrap();
+        assert_eq!("Jpan", gf.primary_language(kosugi).script());
+    }
+
+    #[test]
+    fn effective_axis_defaults() {
+        assert_eq!(Some(14.0), registered_axis_default("opsz"));
+        assert_eq!(None, registered_axis_default("XOPQ"));
+
+        let mut roboto = read_family(&testdata_file_content("roboto-metadata.pb")).unwrap();
+        assert_eq!(Some(400.0), roboto.effective_axis_default("wght"));
+        roboto
+            .registry_default_overrides
+            .insert("wght".to_string(), 300.0);
+        assert_eq!(Some(300.0), roboto.effective_axis_default("wght"));
+        assert_eq!(Some(100.0), roboto.effective_axis_default("wdth"));
+        assert!(
+            roboto
+                .axes_summary()
+                .contains(&("wght", 100.0, 300.0, 900.0))
+        );
+        assert!(default_instance(&roboto).is_none());
+    }
+
+    #[test]
+    fn parse_report_lists_failures() {
+        let root = temp_repo_with_families(
+            "parse_report_lists_failures",
+            &[
+                ("ofl/testsans", "name: \"Test Sans\""),
+                ("ofl/broken", "fonts { weight: \"heavy\" }"),
+            ],
+        );
+        let gf = GoogleFonts::new(root.clone(), None);
+        let report = gf.parse_report();
+        assert_eq!((1, 1), (report.parsed, report.failed));
+        assert_eq!(root.join("ofl/broken/METADATA.pb"), report.failures[0].0);
+        #[cfg(feature = "serde")]
+        assert_eq!(1, report.to_json()["failures"].as_array().unwrap().len());
+    }
+
+    #[test]
+    fn named_instances_from_wght_fallbacks() {
+        let roboto = read_family(&testdata_file_content("roboto-metadata.pb")).unwrap();
+        let italic = named_instances(&roboto, &roboto.fonts[1]);
+        assert_eq!(9, italic.len());
+        assert_eq!((100.0, "Thin Italic".to_string()), italic[0]);
+        assert_eq!((400.0, "Italic".to_string()), italic[3]);
+
+        let family = test_family(
+            "fonts { style: \"normal\" weight: 680 filename: \"TestSans-Heavyish.ttf\" }",
+        );
+        assert_eq!(
+            vec![(680.0, "Bold".to_string())],
+            named_instances(&family, &family.fonts[0])
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "skrifa")]
+    fn test_unicode_range_codepoints() {
+        let cps: Vec<u32> =
+            subsets::unicode_range_codepoints("U+0041-0043, U+0131, bogus").collect();
+        assert_eq!(vec![0x41, 0x42, 0x43, 0x131], cps);
+        assert!(subsets::ranged_subsets().all(|s| subset_unicode_range(s).is_some()));
+    }
+
+    #[test]
+    #[cfg(feature = "skrifa")]
+    fn subset_mismatch_against_cmap() {
+        let root = temp_repo_with_families(
+            "subset_mismatch_against_cmap",
+            &[(
+                "ofl/testsans",
+                "name: \"Test Sans\" subsets: \"menu\" subsets: \"greek\" fonts {}",
+            )],
+        );
+        fs::copy(
+            testdata_dir().join("TestSans-Regular.ttf"),
+            root.join("ofl/testsans/TestSans-400.ttf"),
+        )
+        .unwrap();
+        let gf = GoogleFonts::new(root, None);
+        let (_, family) = gf.family_by_name("Test Sans").unwrap();
+        assert_eq!(
+            SubsetMismatch {
+                uncovered: vec!["greek".to_string()],
+                undeclared: vec!["latin".to_string()],
+            },
+            gf.subset_mismatch(family).unwrap()
+        );
+    }
+
+    #[test]
+    fn families_sorted_by_path() {
+        let metadata = testdata_file_content("roboto-metadata.pb");
+        let root = temp_repo_with_families(
+            "families_sorted_by_path",
+            &["ufl/c", "ofl/b", "apache/a", "ofl/a"].map(|dir| (dir, metadata.as_str())),
+        );
+        let gf = GoogleFonts::builder().repo(root.clone()).build();
+        let paths: Vec<_> = gf
+            .families()
+            .iter()
+            .map(|e| e.path().to_path_buf())
+            .collect();
+        let mut sorted = paths.clone();
+        sorted.sort();
+        assert_eq!(4, paths.len());
+        assert_eq!(sorted, paths);
+        assert_eq!(paths, gf.metadata_paths());
+    }
+
+    #[test]
+    fn read_font_binary_bytes() {
+        let root = temp_repo_with_families(
+            "read_font_binary_bytes",
+            &[("ofl/roboto", &testdata_file_content("roboto-metadata.pb"))],
+        );
+        let dir = root.join("ofl/roboto");
+        let gf = GoogleFonts::builder().repo(root).build();
+        let (_, family) = gf.family_by_name("Roboto").unwrap();
+        let font = &family.fonts[0];
+        assert_eq!(None, gf.read_font_binary(font).unwrap());
+        fs::write(dir.join(font.filename()), b"fontdata").unwrap();
+        assert_eq!(
+            Some(b"fontdata".to_vec()),
+            gf.read_font_binary(font).unwrap()
+        );
+    }
+
+    #[test]
+    fn languages_above_population_threshold() {
+        let gf = GoogleFonts::new(PathBuf::from("/nonexistent"), None);
+        let all = gf.languages_for_script("Latn");
+        let major = gf.languages_with_min_population("Latn", 10_000_000);
+        assert!(!major.is_empty());
+        assert!(major.len() < all.len());
+        assert!(major.iter().all(|l| l.population() >= 10_000_000));
+        assert!(
+            major
+                .windows(2)
+                .all(|w| w[0].population() >= w[1].population())
+        );
+    }
+
+    #[test]
+    fn valid_designspace_locations() {
+        let gf = GoogleFonts::new(PathBuf::from("/nonexistent"), None);
+        let roboto = read_family(&testdata_file_content("roboto-metadata.pb")).unwrap();
+        let valid = |loc: &str| gf.is_valid_location(&roboto, &loc.parse().unwrap());
+        assert!(valid(""));
+        assert!(valid("wght@900"));
+        assert!(valid("ital,wdth,wght@1,75,400"));
+        assert!(!valid("wght@1000"));
+        assert!(!valid("wdth@50"));
+        assert!(!valid("opsz@14"));
+    }
+
+    #[test]
+    fn families_paired_with_primary_language() {
+        let roboto = read_family(&testdata_file_content("roboto-metadata.pb")).unwrap();
+        let kosugi = read_family(&testdata_file_content("kosugimaru-metadata.pb")).unwrap();
+        let gf = GoogleFonts::from_families(vec![
+            (PathBuf::from("ofl/roboto/METADATA.pb"), roboto),
+            (PathBuf::from("ofl/kosugimaru/METADATA.pb"), kosugi),
+        ]);
+        let pairs: Vec<_> = gf
+            .families_with_language()
+            .map(|(family, lang)| (family.name(), lang.script()))
+            .collect();
+        assert_eq!(vec![("Kosugi Maru", "Jpan"), ("Roboto", "Latn")], pairs);
+    }
+
+    #[test]
+    fn region_and_script_lookup() {
+        let gf = GoogleFonts::new(PathBuf::from("/nonexistent"), None);
+        assert_eq!("India", gf.region("IN").unwrap().name());
+        assert_eq!("Devanagari", gf.script("Deva").unwrap().name());
+        assert!(gf.region("XX").is_none());
+        assert!(gf.script("Zzzz").is_none());
+        let root = Path::new("/nonexistent");
+        assert!(iter_regions(root).any(|r| r.unwrap().id() == "IN"));
+        assert!(iter_scripts(root).any(|s| s.unwrap().id() == "Deva"));
+    }
+
+    #[test]
+    fn families_credited_to_designer() {
+        let mut a = FamilyProto::new();
+        a.set_name("A".to_string());
+        a.set_designer("Jane Doe, John Smith".to_string());
+        let mut b = FamilyProto::new();
+        b.set_name("B".to_string());
+        b.set_designer("Janet Doe".to_string());
+        let gf = GoogleFonts::from_families(vec![
+            (PathBuf::from("ofl/a/METADATA.pb"), a),
+            (PathBuf::from("ofl/b/METADATA.pb"), b),
+        ]);
+        let names = |designer: &str| -> Vec<&str> {
+            gf.families_by_designer(designer)
+                .iter()
+                .map(|f| f.name())
+                .collect()
+        };
+        assert_eq!(vec!["A"], names("  john   SMITH "));
+        assert_eq!(vec!["B"], names("Janet Doe"));
+        assert!(names("Jane").is_empty());
+    }
+
+    #[test]
+    fn select_font_weighs_every_weight_difference() {
+        let family = test_family(
+            r#"
+            fonts { style: "normal" weight: 350 filename: "TestSans-350.ttf" }
+            fonts { style: "normal" weight: 450 filename: "TestSans-450.ttf" }
+            fonts { style: "normal" weight: 499 filename: "TestSans-499.ttf" }
+            "#,
+        );
+        let pick = |weight| {
+            select_font(&family, FontStyle::Normal, weight)
+                .unwrap()
+                .filename()
+        };
+        // Equally close weights go to the heavier font
+        assert_eq!("TestSans-450.ttf", pick(400));
+        assert_eq!("TestSans-450.ttf", exemplar(&family).unwrap().filename());
+        // Nearer weights win even within the same hundred
+        assert_eq!("TestSans-350.ttf", pick(390));
+        assert_eq!("TestSans-499.ttf", pick(480));
+        assert_eq!("TestSans-450.ttf", pick(460));
+    }
+
+    #[test]
+    fn empty_family_is_handled() {
+        let mut family = FamilyProto::new();
+        family.set_name("Empty".to_string());
+        family.set_primary_script("Zzzz".to_string());
+        assert!(exemplar(&family).is_none());
+        assert!(exemplar_for_style(&family, FontStyle::Italic).is_none());
+        assert!(best_per_style(&family).is_empty());
+        assert!(default_instance(&family).is_none());
+        assert!(menu_font(&family).is_none());
+        assert!(select_font(&family, FontStyle::Normal, 400).is_none());
+        assert!(select_font_at_most(&family, FontStyle::Normal, 400).is_none());
+        assert!(select_font_by_weight_name(&family, FontStyle::Normal, "bold").is_none());
+        assert!(style_grid(&family).is_empty());
+        assert!(css_font_face(&family, "").is_empty());
+        assert!(nonstandard_weights(&family).is_empty());
+        assert!(family.weight_range().is_none());
+        assert!(family.copyright_holder().is_none());
+        assert!(family.font_for(FontStyle::Normal, 400).is_none());
+
+        let gf = GoogleFonts::from_families(vec![(
+            PathBuf::from("ofl/empty/METADATA.pb"),
+            family.clone(),
+        )]);
+        assert_eq!("en_Latn", gf.primary_language(&family).id());
+        assert!(gf.missing_font_binaries(&family).is_empty());
+        assert_eq!(0, gf.family_binary_size(&family).unwrap());
+        assert!(
+            gf.instances_matching(&[("wght".to_string(), 400.0)], 0.0)
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn fonts_in_display_order() {
+        let family = test_family(
+            r#"
+            fonts { style: "italic" weight: 700 filename: "TestSans-BoldItalic.ttf" }
+            fonts { style: "normal" weight: 700 filename: "TestSans-Bold.ttf" }
+            fonts { style: "italic" weight: 400 filename: "TestSans-Italic[wght].ttf" }
+            fonts { style: "normal" weight: 300 filename: "TestSans-Light.ttf" }
+            fonts { style: "normal" weight: 400 filename: "TestSans[wght].ttf" }
+            "#,
+        );
+        assert_eq!(
+            vec![
+                "TestSans[wght].ttf",
+                "TestSans-Light.ttf",
+                "TestSans-Bold.ttf",
+                "TestSans-Italic[wght].ttf",
+                "TestSans-BoldItalic.ttf",
+            ],
+            family
+                .fonts_display_order()
+                .iter()
+                .map(|f| f.filename())
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn read_single_tags_file() {
+        let root = temp_repo_with_tags(
+            "read_single_tags_file",
+            &[
+                ("a.csv", "Roboto,/Quality/Drawing,80\n"),
+                ("b.csv", "# comment\nLobster,/Script/Handwritten,100\n"),
+            ],
+        );
+        let tags = read_tags_file(&root.join("tags/all/b.csv")).unwrap();
+        assert_eq!(1, tags.len());
+        assert_eq!("Lobster", tags[0].family);
+        assert_eq!(2, read_tags(&root).unwrap().len());
+        assert!(matches!(
+            read_tags_file(&root.join("tags/all/missing.csv")),
+            Err(GfError::NotFound(_))
+        ));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinks_out_of_repo_are_ignored() {
+        use std::os::unix::fs::symlink;
+        let outside = std::env::temp_dir().join("gf-metadata-symlink-outside");
+        let _ = fs::remove_dir_all(&outside);
+        fs::create_dir_all(&outside).unwrap();
+        let metadata = testdata_file_content("roboto-metadata.pb");
+        fs::write(outside.join("METADATA.pb"), &metadata).unwrap();
+
+        let root = temp_repo_with_families(
+            "symlinks_out_of_repo_are_ignored",
+            &[("ofl/inside", &metadata)],
+        );
+        fs::create_dir_all(root.join("ofl/stray")).unwrap();
+        symlink(
+            outside.join("METADATA.pb"),
+            root.join("ofl/stray/METADATA.pb"),
+        )
+        .unwrap();
+        symlink(&outside, root.join("ofl/linked")).unwrap();
+
+        let gf = GoogleFonts::builder().repo(root.clone()).build();
+        assert_eq!(
+            vec![root.join("ofl/inside/METADATA.pb")],
+            gf.metadata_paths()
+        );
+        assert_eq!(1, gf.families().len());
+    }
+
+    #[test]
+    fn orphan_font_files_are_undeclared_binaries() {
+        let root = temp_repo_with_families(
+            "orphan_font_files_are_undeclared_binaries",
+            &[("ofl/roboto", &testdata_file_content("roboto-metadata.pb"))],
+        );
+        let dir = root.join("ofl/roboto");
+        for file in [
+            "Roboto[wdth,wght].ttf",
+            "Roboto-Old.TTF",
+            "Roboto-Bold.otf",
+            "OFL.txt",
+        ] {
+            fs::write(dir.join(file), b"").unwrap();
+        }
+        let gf = GoogleFonts::builder().repo(root).build();
+        let (_, family) = gf.family_by_name("Roboto").unwrap();
+        assert_eq!(
+            vec![dir.join("Roboto-Bold.otf"), dir.join("Roboto-Old.TTF")],
+            gf.orphan_font_files(family).unwrap()
+        );
+        assert!(
+            gf.orphan_font_files(&FamilyProto::new())
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn typed_classifications() {
+        let mut mono = FamilyProto::new();
+        mono.set_name("Mono".to_string());
+        mono.classifications = vec!["monospace".to_string(), "Retro".to_string()];
+        let mut display = FamilyProto::new();
+        display.set_name("Display".to_string());
+        display.classifications = vec![" DISPLAY ".to_string()];
+        assert_eq!(
+            vec![
+                Classification::Monospace,
+                Classification::Other("Retro".to_string())
+            ],
+            mono.classifications_typed()
+        );
+        let gf = GoogleFonts::from_families(vec![
+            (PathBuf::from("ofl/mono/METADATA.pb"), mono),
+            (PathBuf::from("ofl/display/METADATA.pb"), display),
+        ]);
+        let names = |c| -> Vec<&str> {
+            gf.families_with_classification(c)
+                .iter()
+                .map(|f| f.name())
+                .collect()
+        };
+        assert_eq!(vec!["Display"], names(Classification::Display));
+        assert_eq!(vec!["Mono"], names(Classification::Monospace));
+        assert!(names(Classification::Symbols).is_empty());
+        assert_eq!("HANDWRITING", Classification::Handwriting.to_string());
+        assert_eq!(Classification::Symbols, " symbols ".parse().unwrap());
+    }
+
+    #[test]
+    fn sample_falls_back_to_script_pangram() {
+        let cyrillic = LanguageProto {
+            script: Some("Cyrl".to_string()),
+            ..Default::default()
+        };
+        assert!(sample_or_pangram(&cyrillic).starts_with("Съешь"));
+        let unknown = LanguageProto {
+            script: Some("Zzzz".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            "The quick brown fox jumps over the lazy dog.",
+            sample_or_pangram(&unknown)
+        );
+        let gf = GoogleFonts::new(PathBuf::from("/nonexistent"), None);
+        let en = gf.language_exact("en_Latn").unwrap();
+        assert_eq!(
+            language_sample_text(en).map(str::to_string),
+            Some(sample_or_pangram(en))
+        );
+    }
+
+    #[test]
+    fn read_tags_from_later_dirs_override() {
+        let root = temp_repo_with_tags(
+            "read_tags_from_later_dirs_override",
+            &[(
+                "families.csv",
+                "Roboto,/Quality/Drawing,80\nRoboto,/Sans/Geometric,50\n",
+            )],
+        );
+        let overrides = root.join("tags/local");
+        fs::create_dir_all(&overrides).unwrap();
+        fs::write(
+            overrides.join("local.csv"),
+            "Roboto,/Sans/Geometric,90\nLobster,/Script/Handwritten,100\n",
+        )
+        .unwrap();
+        let tags = read_tags_from(&[&root.join("tags/all"), &overrides]).unwrap();
+        let summary: Vec<_> = tags
+            .iter()
+            .map(|t| (t.family.as_str(), t.tag.as_str(), t.value))
+            .collect();
+        assert_eq!(
+            vec![
+                ("Roboto", "/Quality/Drawing", 80.0),
+                ("Roboto", "/Sans/Geometric", 90.0),
+                ("Lobster", "/Script/Handwritten", 100.0),
+            ],
+            summary
+        );
+    }
+
+    #[test]
+    fn parse_from_str_matches_read_family() {
+        let content = testdata_file_content("roboto-metadata.pb");
+        let family = parse_from_str(&content).unwrap();
+        assert_eq!("Roboto", family.name());
+        assert_eq!(read_family(&content).unwrap(), family);
+    }
+
+    #[test]
+    fn families_with_axis_match_exact_tag() {
+        let roboto = read_family(&testdata_file_content("roboto-metadata.pb")).unwrap();
+        let kosugi = read_family(&testdata_file_content("kosugimaru-metadata.pb")).unwrap();
+        let gf = GoogleFonts::from_families(vec![
+            (PathBuf::from("ofl/roboto/METADATA.pb"), roboto),
+            (PathBuf::from("ofl/kosugimaru/METADATA.pb"), kosugi),
+        ]);
+        let names = |tag| -> Vec<&str> {
+            gf.families_with_axis(tag)
+                .iter()
+                .map(|f| f.name())
+                .collect()
+        };
+        assert_eq!(vec!["Roboto"], names("wdth"));
+        assert!(names("WDTH").is_empty());
+        assert!(names("opsz").is_empty());
+    }
+
+    #[test]
+    fn sample_text_for_explicit_language() {
+        let gf = GoogleFonts::new(PathBuf::from("/nonexistent"), None);
+        let english = gf.language("en_Latn").unwrap();
+        assert_eq!(language_sample_text(english), gf.sample_text_in("en_Latn"));
+        assert!(gf.sample_text_in("en_Latn").is_some_and(|s| !s.is_empty()));
+        assert_eq!(None, gf.sample_text_in("xx_Nope"));
+    }
+
+    #[test]
+    fn covered_languages_across_families() {
+        let gf = GoogleFonts::from_families(vec![
+            (
+                PathBuf::from("ofl/testsans/METADATA.pb"),
+                test_family("name: \"Test Sans\" subsets: \"menu\" subsets: \"greek\""),
+            ),
+            (
+                PathBuf::from("ofl/testserif/METADATA.pb"),
+                test_family("name: \"Test Serif\" languages: \"ja_Jpan\""),
+            ),
+        ]);
+        let covered = gf.covered_languages();
+        let ids: Vec<&str> = covered.iter().map(|l| l.id()).collect();
+        assert!(ids.contains(&"el_Grek"));
+        assert!(ids.contains(&"ja_Jpan"));
+        assert!(!ids.contains(&"en_Latn"));
+        assert!(
+            covered
+                .iter()
+                .all(|l| l.script() == "Grek" || l.id() == "ja_Jpan")
+        );
+        assert!(
+            covered
+                .windows(2)
+                .all(|w| w[0].population() >= w[1].population())
+        );
+    }
+
+    #[test]
+    fn primary_language_conflicts_with_script() {
+        let gf = GoogleFonts::from_families(vec![
+            (
+                PathBuf::from("ofl/testsans/METADATA.pb"),
+                test_family(
+                    "name: \"Test Sans\" primary_language: \"ru_Cyrl\" primary_script: \"Latn\"",
+                ),
+            ),
+            (
+                PathBuf::from("ofl/testserif/METADATA.pb"),
+                test_family(
+                    "name: \"Test Serif\" primary_language: \"ru_Cyrl\" primary_script: \"Cyrl\"",
+                ),
+            ),
+            (
+                PathBuf::from("ofl/testmono/METADATA.pb"),
+                test_family("name: \"Test Mono\" primary_language: \"ru_Cyrl\""),
+            ),
+        ]);
+        assert_eq!(
+            vec![("Test Sans", "ru_Cyrl", "Latn")],
+            gf.primary_language_script_conflicts()
+                .into_iter()
+                .map(|(f, lang, script)| (f.name(), lang, script))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn combined_exemplars_follow_subsets() {
+        let gf = GoogleFonts::new(PathBuf::from("/nonexistent"), None);
+        let family = test_family(
+            "name: \"Test Sans\" subsets: \"menu\" subsets: \"latin\" subsets: \"latin-ext\" subsets: \"greek\"",
+        );
+        let chars = gf.combined_exemplar_chars(&family);
+        let latin = language_base_chars(most_populous_language("Latn").unwrap());
+        let greek = language_base_chars(most_populous_language("Grek").unwrap());
+        assert!(chars.starts_with(&latin));
+        assert!(greek.iter().all(|c| chars.contains(c)));
+        let mut deduped = chars.clone();
+        deduped.sort();
+        deduped.dedup();
+        assert_eq!(chars.len(), deduped.len());
+        assert!(
+            gf.combined_exemplar_chars(&test_family("subsets: \"menu\""))
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn reserved_font_name_from_license() {
+        let root = temp_repo_with_families(
+            "reserved_font_name_from_license",
+            &[
+                ("ofl/testsans", "name: \"Test Sans\" fonts {}"),
+                ("ofl/testserif", "name: \"Test Serif\" fonts {}"),
+            ],
+        );
+        fs::write(
+            root.join("ofl/testsans/OFL.txt"),
+            "Copyright 2020 The Test Sans Project Authors, with Reserved Font Name \"Test Sans\".\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("ofl/testserif/OFL.txt"),
+            "Copyright 2020 The Test Serif Project Authors\n",
+        )
+        .unwrap();
+        let gf = GoogleFonts::new(root, None);
+        let rfn = |name| gf.reserved_font_name(gf.family_by_name(name).unwrap().1);
+        assert_eq!(Some("Test Sans".to_string()), rfn("Test Sans"));
+        assert_eq!(None, rfn("Test Serif"));
+        assert_eq!(
+            None,
+            gf.reserved_font_name(&test_family("name: \"Lora\" fonts {}"))
+        );
+    }
+
+    #[test]
+    fn instances_matching_coordinates() {
+        let roboto = read_family(&testdata_file_content("roboto-metadata.pb")).unwrap();
+        let gf = GoogleFonts::from_families(vec![
+            (PathBuf::from("ofl/roboto/METADATA.pb"), roboto),
+            (
+                PathBuf::from("ofl/testsans/METADATA.pb"),
+                test_family(
+                    r#"
+                    name: "Test Sans"
+                    fonts { style: "normal" weight: 400 }
+                    fonts { style: "normal" weight: 700 }
+                    "#,
+                ),
+            ),
+        ]);
+        let matching = |coords: &[(&str, f32)], tolerance| {
+            let coords: Vec<_> = coords.iter().map(|(t, v)| (t.to_string(), *v)).collect();
+            gf.instances_matching(&coords, tolerance)
+                .into_iter()
+                .map(|(_, font)| font.filename())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            vec!["Roboto[wdth,wght].ttf", "TestSans-700.ttf"],
+            matching(&[("wght", 700.0), ("ital", 0.0)], 0.0)
+        );
+        assert_eq!(
+            vec![
+                "Roboto[wdth,wght].ttf",
+                "Roboto-Italic[wdth,wght].ttf",
+                "TestSans-700.ttf"
+            ],
+            matching(&[("wght", 650.0)], 50.0)
+        );
+        assert_eq!(
+            vec!["Roboto[wdth,wght].ttf", "Roboto-Italic[wdth,wght].ttf"],
+            matching(&[("wdth", 80.0)], 0.0)
+        );
+        assert!(matching(&[("wdth", 50.0)], 0.0).is_empty());
+    }
+
+    #[test]
+    fn primary_language_counts() {
+        let family = |name: &str, lang: &str| {
+            (
+                PathBuf::from(format!("ofl/{name}/METADATA.pb")),
+                test_family(&format!("name: \"{name}\" primary_language: \"{lang}\"")),
+            )
+        };
+        let gf = GoogleFonts::from_families(vec![
+            family("a", "ru_Cyrl"),
+            family("b", "en_Latn"),
+            family("c", "ru_Cyrl"),
+            family("d", "el_Grek"),
+        ]);
+        assert_eq!(
+            vec![("ru_Cyrl", 2), ("el_Grek", 1), ("en_Latn", 1)],
+            gf.primary_language_distribution()
+                .into_iter()
+                .map(|(l, n)| (l.id(), n))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn concurrent_loading_matches_serial() {
+        let root = temp_repo_with_families(
+            "concurrent_loading_matches_serial",
+            &[
+                ("ofl/a", "name: \"A\""),
+                ("ofl/b", "name: \"B\""),
+                ("ofl/c", "fonts { weight: \"heavy\" }"),
+                ("ofl/d", "name: \"D\""),
+                ("apache/e", "name: \"E\""),
+            ],
+        );
+        let load = |concurrency| {
+            GoogleFonts::builder()
+                .repo(root.clone())
+                .concurrency(concurrency)
+                .build()
+                .families()
+                .iter()
+                .map(|e| {
+                    (
+                        e.path().to_path_buf(),
+                        e.family().map(|f| f.name().to_string()),
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+        let serial = load(0);
+        assert_eq!(5, serial.len());
+        assert_eq!(4, serial.iter().filter(|(_, f)| f.is_some()).count());
+        assert_eq!(serial, load(3));
+        assert_eq!(serial, load(16));
+    }
+
+    #[test]
+    fn exemplar_sanity_flags_heavier_variable_font() {
+        let gf = GoogleFonts::from_families(vec![
+            (
+                PathBuf::from("ofl/roboto/METADATA.pb"),
+                read_family(&testdata_file_content("roboto-metadata.pb")).unwrap(),
+            ),
+            (
+                PathBuf::from("ofl/testsans/METADATA.pb"),
+                test_family(
+                    r#"
+                    name: "Test Sans"
+                    fonts { weight: 400 filename: "TestSans-Regular.ttf" }
+                    fonts { weight: 500 filename: "TestSans[wght].ttf" }
+                    "#,
+                ),
+            ),
+        ]);
+        assert_eq!(
+            vec![("Test Sans", "a font closer to weight 400 exists")],
+            gf.exemplar_sanity_issues()
+                .into_iter()
+                .map(|(f, issue)| (f.name(), issue))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn read_tags_from_layers_directories() {
+        let root = temp_repo_with_tags(
+            "read_tags_from_layers_directories",
+            &[(
+                "families.csv",
+                "Roboto,/Quality/Drawing,90\nLora,/Expressive/Calm,40\n",
+            )],
+        );
+        let staging = root.join("tags/staging");
+        fs::create_dir_all(&staging).unwrap();
+        fs::write(
+            staging.join("families.csv"),
+            "Lora,/Expressive/Calm,70\nInter,/Quality/Drawing,60\n",
+        )
+        .unwrap();
+        let tags = read_tags_from(&[&root.join("tags/all"), &staging]).unwrap();
+        assert_eq!(
+            vec![("Roboto", 90.0), ("Lora", 70.0), ("Inter", 60.0)],
+            tags.iter()
+                .map(|t| (t.family.as_str(), t.value))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            read_tags(&root).unwrap(),
+            read_tags_from(&[&root.join("tags/all")]).unwrap()
+        );
+    }
+
+    #[test]
+    fn monospace_from_category_or_classification() {
+        assert!(is_monospace(&test_family("category: \"MONOSPACE\"")));
+        assert!(is_monospace(&test_family(
+            "category: \"SANS_SERIF\" classifications: \"monospace\""
+        )));
+        assert!(!is_monospace(&test_family("category: \"SANS_SERIF\"")));
+        let roboto = read_family(&testdata_file_content("roboto-metadata.pb")).unwrap();
+        assert!(!is_monospace(&roboto));
+    }
+
+    #[test]
+    fn family_mtime_reads_metadata_file() {
+        let root = temp_repo_with_families(
+            "family_mtime_reads_metadata_file",
+            &[("ofl/testsans", "name: \"Test Sans\" fonts {}")],
+        );
+        let modified = SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_600_000_000);
+        fs::File::options()
+            .write(true)
+            .open(root.join("ofl/testsans/METADATA.pb"))
+            .unwrap()
+            .set_modified(modified)
+            .unwrap();
+        let gf = GoogleFonts::new(root, None);
+        let (_, family) = gf.family_by_name("Test Sans").unwrap();
+        assert_eq!(Some(modified), gf.family_mtime(family));
+        assert_eq!(
+            None,
+            gf.family_mtime(&test_family("name: \"Lora\" fonts {}"))
+        );
+    }
+
+    #[test]
+    fn sample_script_mismatches_need_declared_subset() {
+        let gf = GoogleFonts::from_families(vec![
+            (
+                PathBuf::from("ofl/testsans/METADATA.pb"),
+                test_family("name: \"Test Sans\" subsets: \"latin\" primary_language: \"ru_Cyrl\""),
+            ),
+            (
+                PathBuf::from("ofl/testserif/METADATA.pb"),
+                test_family(
+                    "name: \"Test Serif\" subsets: \"cyrillic\" primary_language: \"ru_Cyrl\"",
+                ),
+            ),
+        ]);
+        assert_eq!(
+            vec![("Test Sans", "ru_Cyrl", "Cyrl")],
+            gf.sample_script_mismatches()
+                .into_iter()
+                .map(|(f, lang, script)| (f.name(), lang, script))
+                .collect::<Vec<_>>()
+        );
     }
-    /// Return the parsed tag entries for the repository.
-    ///
-    /// On first call this will read and parse the CSV files from the repo's
-    /// `tags/all` directory. Returns `Ok(&[Tag])` when parsing succeeded, or
-    /// `Err(&Error)` if an I/O or parse error occurred. The returned slice is
-    /// borrowed from internal storage and remains valid for the lifetime of
-    /// `self`.
-    pub fn tags(&self) -> Result<&[Tagging], &Error> {
-        self.tags
-            .get_or_init(|| read_tags(&self.repo_dir))
-            .as_ref()
-            .map(|tags| tags.as_slice())
+
+    #[test]
+    fn families_with_several_variable_fonts() {
+        let roboto = read_family(&testdata_file_content("roboto-metadata.pb")).unwrap();
+        let kosugi = read_family(&testdata_file_content("kosugimaru-metadata.pb")).unwrap();
+        let gf = GoogleFonts::from_families(vec![
+            (PathBuf::from("ofl/roboto/METADATA.pb"), roboto),
+            (PathBuf::from("ofl/kosugimaru/METADATA.pb"), kosugi),
+        ]);
+        let multiple = gf.families_with_multiple_vfs();
+        assert_eq!(1, multiple.len());
+        let (family, vfs) = &multiple[0];
+        assert_eq!("Roboto", family.name());
+        assert_eq!(
+            vec!["Roboto[wdth,wght].ttf", "Roboto-Italic[wdth,wght].ttf"],
+            vfs.iter().map(|f| f.filename()).collect::<Vec<_>>()
+        );
     }
-    /// Return tag metadata (min/max and prompt names) for tags defined in
-    /// the repository.
-    ///
-    /// This reads `tags/tags_metadata.csv` on first access and returns a
-    /// borrowed slice on success. Errors are returned as `Err(&Error)`.
-    pub fn tag_metadata(&self) -> Result<&[TagMetadata], &Error> {
-        self.tag_metadata
-            .get_or_init(|| read_tag_metadata(&self.repo_dir))
-            .as_ref()
-            .map(|metadata| metadata.as_slice())
+
+    #[test]
+    fn language_names_and_autonyms() {
+        let gf = GoogleFonts::new(PathBuf::from("/nonexistent"), None);
+        assert_eq!(Some("Japanese"), gf.language_name("ja_Jpan"));
+        assert!(
+            gf.language_autonym("ja_Jpan")
+                .is_some_and(|a| a.starts_with("日本語"))
+        );
+        assert_eq!(None, gf.language_name("xx_Nope"));
+        assert_eq!(None, gf.language_autonym("xx_Nope"));
     }
-    /// Return a list of discovered families and their parsed metadata.
-    ///
-    /// Each entry is a tuple `(PathBuf, Result<FamilyProto, ParseError>)`.
-    /// The `PathBuf` is the path to the `METADATA.pb` file for the family.
-    /// The `Result` contains the parsed `FamilyProto` on success or a
-    /// `ParseError` if the metadata could not be parsed. Families are
-    /// discovered lazily by scanning the repository and applying the
-    /// `family_filter` provided at construction (if any).
-    ///
-    /// The returned slice is borrowed from internal storage and stays valid
-    /// for the lifetime of `self`.
-    pub fn families(&self) -> &[(PathBuf, Result<FamilyProto, ParseError>)] {
-        self.families
-            .get_or_init(|| iter_families(&self.repo_dir, self.family_filter.as_ref()).collect())
-            .as_slice()
+
+    #[test]
+    fn nonstandard_static_weights() {
+        let family = test_family(
+            r#"
+            fonts { weight: 350 filename: "TestSans-Book.ttf" }
+            fonts { weight: 450 filename: "TestSans-Medium.ttf" }
+            fonts { weight: 950 filename: "TestSans-Ultra.ttf" }
+            fonts { weight: 420 filename: "TestSans[wght].ttf" }
+            "#,
+        );
+        assert_eq!(
+            vec![("TestSans-Medium.ttf", 450), ("TestSans-Ultra.ttf", 950)],
+            nonstandard_weights(&family)
+                .into_iter()
+                .map(|(f, w)| (f.filename(), w))
+                .collect::<Vec<_>>()
+        );
     }
-    /// Lookup a language by its identifier.
-    ///
-    /// The `lang_id` should be the language identifier used by the
-    /// `google-fonts-languages` crate (for example "en_Latn"). Returns
-    /// `Some(&LanguageProto)` if the language is known, otherwise `None`.
-    /// This is a simple passthrough to the bundled `LANGUAGES` map.
-    pub fn language(&self, lang_id: &str) -> Option<&LanguageProto> {
-        LANGUAGES.get(lang_id).map(|l| &**l)
+
+    #[test]
+    fn distinct_axes_counts_families() {
+        let roboto = read_family(&testdata_file_content("roboto-metadata.pb")).unwrap();
+        let gf = GoogleFonts::from_families(vec![
+            (PathBuf::from("ofl/roboto/METADATA.pb"), roboto),
+            (
+                PathBuf::from("ofl/testsans/METADATA.pb"),
+                test_family(
+                    r#"
+                    name: "Test Sans"
+                    axes { tag: "wght" min_value: 100 max_value: 900 }
+                    axes { tag: "GRAD" min_value: -50 max_value: 50 }
+                    "#,
+                ),
+            ),
+        ]);
+        assert_eq!(
+            vec![
+                ("wght".to_string(), 2),
+                ("GRAD".to_string(), 1),
+                ("wdth".to_string(), 1)
+            ],
+            gf.distinct_axes()
+        );
     }
 
-    fn family_by_font_file(&self) -> &HashMap<String, usize> {
-        self.family_by_font_file.get_or_init(|| {
-            self.families()
+    #[test]
+    fn exact_style_never_settles() {
+        let roboto = read_family(&testdata_file_content("roboto-metadata.pb")).unwrap();
+        assert!(has_exact_style(&roboto, 350, FontStyle::Italic));
+        assert!(!has_exact_style(&roboto, 950, FontStyle::Normal));
+        let family = test_family("fonts { style: \"normal\" weight: 400 }");
+        assert!(has_exact_style(&family, 400, FontStyle::Normal));
+        assert!(!has_exact_style(&family, 500, FontStyle::Normal));
+        assert!(!has_exact_style(&family, 400, FontStyle::Italic));
+    }
+
+    #[test]
+    fn families_ranked_by_design_space() {
+        let roboto = read_family(&testdata_file_content("roboto-metadata.pb")).unwrap();
+        let kosugi = read_family(&testdata_file_content("kosugimaru-metadata.pb")).unwrap();
+        let gf = GoogleFonts::from_families(vec![
+            (PathBuf::from("ofl/kosugimaru/METADATA.pb"), kosugi),
+            (PathBuf::from("ofl/roboto/METADATA.pb"), roboto),
+            (
+                PathBuf::from("ofl/aaa/METADATA.pb"),
+                test_family("name: \"Aaa\""),
+            ),
+            (
+                PathBuf::from("ofl/testsans/METADATA.pb"),
+                test_family(
+                    "name: \"Test Sans\" axes { tag: \"wght\" min_value: 100 max_value: 900 }",
+                ),
+            ),
+        ]);
+        assert_eq!(
+            vec!["Test Sans", "Roboto", "Aaa", "Kosugi Maru"],
+            gf.families_by_design_space_volume()
                 .iter()
-                .enumerate()
-                .filter(|(_, (_, f))| f.is_ok())
-                .flat_map(|(i, (_, f))| {
-                    f.as_ref()
-                        .unwrap()
-                        .fonts
-                        .iter()
-                        .map(move |f| (f.filename().to_string(), i))
-                })
-                .collect()
-        })
+                .map(|f| f.name())
+                .collect::<Vec<_>>()
+        );
     }
 
-    /// Given a `FontProto`, return the family it belongs to.
-    ///
-    /// If the provided font is known (by filename) this returns `Some((path, family))`
-    /// where `path` is the path to the family's `METADATA.pb` and `family` is
-    /// a borrowed `FamilyProto`. Returns `None` if the font is not present in
-    /// the discovered families.
-    pub fn family(&self, font: &FontProto) -> Option<(&Path, &FamilyProto)> {
-        self.family_by_font_file()
-            .get(font.filename())
-            .copied()
-            .map(|i| {
-                let (p, f) = &self.families()[i];
-                (p.as_path(), f.as_ref().unwrap())
-            })
+    #[test]
+    fn tag_metadata_keeps_file_order() {
+        let root = temp_repo_with_tags("tag_metadata_keeps_file_order", &[]);
+        fs::write(
+            root.join("tags/tags_metadata.csv"),
+            "/Quality/Drawing,0,100,drawing quality\n\
+             /Expressive/Calm,0,100,calm\n\
+             /Expressive/Business,0,100,business\n",
+        )
+        .unwrap();
+        let gf = GoogleFonts::new(root, None);
+        assert_eq!(
+            vec![
+                "/Quality/Drawing",
+                "/Expressive/Calm",
+                "/Expressive/Business"
+            ],
+            gf.tag_metadata_ordered()
+                .iter()
+                .map(|m| m.tag.as_str())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(Some(2), gf.tag_display_index("/Expressive/Business"));
+        assert_eq!(None, gf.tag_display_index("/Expressive/Loud"));
+        let missing = GoogleFonts::new(PathBuf::from("/nonexistent"), None);
+        assert!(missing.tag_metadata_ordered().is_empty());
     }
-    /// Find the path to the font binary for a `FontProto`.
-    ///
-    /// This resolves the font's family, then constructs the filesystem path
-    /// to the font file (sibling to the family's `METADATA.pb`). If the
-    /// resulting file exists its `PathBuf` is returned. If the file cannot
-    /// be found `None` is returned. A diagnostic is printed to stderr when
-    /// the expected file is missing.
-    pub fn find_font_binary(&self, font: &FontProto) -> Option<PathBuf> {
-        let (family_path, _) = self.family(font)?;
-        let mut font_file = family_path.parent().unwrap().to_path_buf();
-        font_file.push(font.filename());
-        if !font_file.exists() {
-            eprintln!("No such file as {font_file:?}");
-        }
-        font_file.exists().then_some(font_file)
+
+    #[test]
+    fn subsets_with_sample_text_are_not_reported() {
+        let gf = GoogleFonts::new(PathBuf::from("/nonexistent"), None);
+        let roboto = read_family(&testdata_file_content("roboto-metadata.pb")).unwrap();
+        assert!(gf.subsets_without_sample_text(&roboto).is_empty());
+        let family = test_family(
+            "subsets: \"menu\" subsets: \"khmer\" subsets: \"tibetan\" subsets: \"made-up\"",
+        );
+        assert!(gf.subsets_without_sample_text(&family).is_empty());
     }
 
-    /// Our best guess at the primary language for this family
-    ///
-    /// Meant to be a good choice for things like rendering a sample string
-    /// Guess the primary language for a family.
-    ///
-    /// The heuristic is:
-    /// 1. If the family declares a `primary_language` that maps to a known
-    ///    language, return that.
-    /// 2. Otherwise if the family declares a `primary_script`, pick the most
-    ///    populous language using that script.
-    /// 3. Fall back to `en_Latn` if nothing else matches.
-    ///
-    /// This is intended as a best-effort choice to select a reasonable
-    /// language for rendering sample text, not as an authoritative mapping.
-    pub fn primary_language(&self, family: &FamilyProto) -> &LanguageProto {
-        // Probe primary lang, primary script, then default baselessly to latin
-        let mut primary_language: Option<&LanguageProto> = None;
-        if primary_language.is_none() && family.has_primary_language() {
-            if let Some(lang) = self.language(family.primary_language()) {
-                primary_language = Some(lang);
-            } else {
-                eprintln!(
-                    "{} specifies invalid primary_language {}",
-                    family.name(),
-                    family.primary_language()
-                );
-            }
-        }
-        if primary_language.is_none() && family.has_primary_script() {
-            // If our script matches many languages pick the one with the highest population
-            let lang = LANGUAGES
-                .values()
-                .filter(|l| l.script.is_some() && l.script() == family.primary_script())
-                .reduce(|acc, e| {
-                    if acc.population() > e.population() {
-                        acc
-                    } else {
-                        e
-                    }
-                });
-            if let Some(lang) = lang {
-                primary_language = Some(lang);
-            } else {
-                eprintln!(
-                    "{} specifies a primary_script that matches no languages {}",
-                    family.name(),
-                    family.primary_script()
-                );
-            }
-        }
-        if primary_language.is_none() {
-            primary_language = self.language("en_Latn");
-        }
-        primary_language
-            .unwrap_or_else(|| panic!("Not even our final fallback worked for {}", family.name()))
+    #[test]
+    fn registered_axis_display_defaults() {
+        let wght = registered_axis("wght").unwrap();
+        assert_eq!("Weight", wght.name);
+        assert_eq!(
+            (1.0, 400.0, 1000.0),
+            (wght.min_value, wght.default_value, wght.max_value)
+        );
+        assert_eq!(Some("Grade"), registered_axis("GRAD").map(|a| a.name));
+        assert!(registered_axis("WGHT").is_none());
+        assert!(registered_axis("XOPQ").is_none());
     }
-}
 
-#[cfg(test)]
-mod tests {
+    #[test]
+    fn families_ranked_by_script_coverage() {
+        let roboto = read_family(&testdata_file_content("roboto-metadata.pb")).unwrap();
+        let gf = GoogleFonts::from_families(vec![
+            (
+                PathBuf::from("ofl/testsans/METADATA.pb"),
+                test_family("name: \"Test Sans\" subsets: \"latin\" subsets: \"menu\""),
+            ),
+            (PathBuf::from("ofl/roboto/METADATA.pb"), roboto),
+            (
+                PathBuf::from("ofl/aaa/METADATA.pb"),
+                test_family("name: \"Aaa\" subsets: \"latin-ext\" subsets: \"vietnamese\""),
+            ),
+        ]);
+        let coverage = gf.families_by_script_coverage();
+        assert_eq!(
+            vec![
+                ("Roboto", vec!["Cyrillic", "Greek", "Latin"]),
+                ("Aaa", vec!["Latin"]),
+                ("Test Sans", vec!["Latin"]),
+            ],
+            coverage
+                .iter()
+                .map(|(f, names)| (f.name(), names.iter().map(String::as_str).collect()))
+                .collect::<Vec<(&str, Vec<&str>)>>()
+        );
+    }
 
-    use std::fs;
+    #[test]
+    fn invalid_tag_locations_reported() {
+        let root = temp_repo_with_tags(
+            "invalid_tag_locations_reported",
+            &[(
+                "families.csv",
+                "Roboto,wght@700,/Quality/Drawing,90\n\
+                 Roboto,wght@950,/Quality/Drawing,90\n\
+                 Roboto,opsz@12,/Quality/Drawing,90\n\
+                 Test Sans,wght@700,/Quality/Drawing,90\n\
+                 Test Sans,ital@1,/Quality/Drawing,90\n\
+                 Lora,wght@2000,/Quality/Drawing,90\n",
+            )],
+        );
+        write_families(
+            &root,
+            &[
+                ("ofl/roboto", &testdata_file_content("roboto-metadata.pb")),
+                (
+                    "ofl/testsans",
+                    "name: \"Test Sans\" fonts { style: \"normal\" weight: 700 }",
+                ),
+            ],
+        );
+        let gf = GoogleFonts::new(root, None);
+        assert_eq!(
+            vec![
+                ("Roboto", "wght value 950 outside 100..900".to_string()),
+                ("Roboto", "axis opsz not declared by family".to_string()),
+                ("Test Sans", "ital@1 but family has no italic".to_string()),
+            ],
+            gf.invalid_tag_locations()
+                .into_iter()
+                .map(|(t, reason)| (t.family.as_str(), reason))
+                .collect::<Vec<_>>()
+        );
+    }
 
-    use super::*;
+    #[test]
+    fn designer_counts_split_credits() {
+        let family = |name: &str, designer: &str| {
+            (
+                PathBuf::from(format!("ofl/{name}/METADATA.pb")),
+                test_family(&format!("name: \"{name}\" designer: \"{designer}\"")),
+            )
+        };
+        let gf = GoogleFonts::from_families(vec![
+            family("a", "Ann Smith, Bob Jones"),
+            family("b", "Bob Jones"),
+            family("c", "Cat Lee,, Bob Jones, Cat Lee"),
+        ]);
+        assert_eq!(
+            vec![
+                ("Bob Jones".to_string(), 3),
+                ("Ann Smith".to_string(), 1),
+                ("Cat Lee".to_string(), 1),
+            ],
+            gf.designer_family_counts()
+        );
+    }
 
-    fn testdata_dir() -> std::path::PathBuf {
-        // cargo test seems to run in the project directory
-        // VSCode test seems to run in the workspace directory
-        // probe for the file we want in hopes of finding it regardless
+    #[test]
+    fn family_by_filename_ignores_case() {
+        let roboto = read_family(&testdata_file_content("roboto-metadata.pb")).unwrap();
+        let gf = GoogleFonts::from_families(vec![
+            (PathBuf::from("ofl/roboto/METADATA.pb"), roboto),
+            (
+                PathBuf::from("apache/roboto/METADATA.pb"),
+                test_family("name: \"Old Roboto\" fonts { filename: \"ROBOTO[wdth,wght].ttf\" }"),
+            ),
+        ]);
+        let (path, family) = gf
+            .family_by_filename_ci("roboto-italic[WDTH,WGHT].TTF")
+            .unwrap();
+        assert_eq!(Path::new("ofl/roboto/METADATA.pb"), path);
+        assert_eq!("Roboto", family.name());
+        assert_eq!(
+            Some("Old Roboto"),
+            gf.family_by_filename_ci("roboto[wdth,wght].ttf")
+                .map(|(_, f)| f.name())
+        );
+        assert!(gf.family_by_filename_ci("Lora.ttf").is_none());
+    }
 
-        ["./resources/testdata", "../resources/testdata"]
-            .iter()
-            .map(std::path::PathBuf::from)
-            .find(|pb| pb.exists())
-            .unwrap()
+    #[test]
+    fn style_grid_combines_static_and_variable() {
+        let family = test_family(
+            r#"
+            fonts { style: "italic" weight: 400 filename: "TestSans-Italic.ttf" }
+            fonts { style: "normal" weight: 400 filename: "TestSans[wght].ttf" }
+            axes { tag: "wght" min_value: 300 max_value: 500 }
+            "#,
+        );
+        assert_eq!(
+            vec![
+                (300, FontStyle::Normal, Some("TestSans[wght].ttf")),
+                (300, FontStyle::Italic, None),
+                (400, FontStyle::Normal, Some("TestSans[wght].ttf")),
+                (400, FontStyle::Italic, Some("TestSans-Italic.ttf")),
+                (500, FontStyle::Normal, Some("TestSans[wght].ttf")),
+                (500, FontStyle::Italic, None),
+            ],
+            style_grid(&family)
+                .into_iter()
+                .map(|(w, s, f)| (w, s, f.map(|f| f.filename())))
+                .collect::<Vec<_>>()
+        );
+        assert!(style_grid(&FamilyProto::new()).is_empty());
     }
 
-    fn testdata_file_content(relative_path: &str) -> String {
-        let mut p = testdata_dir();
-        p.push(relative_path);
-        fs::read_to_string(p).unwrap()
+    #[test]
+    fn tag_vectors_line_up() {
+        let root = temp_repo_with_tags(
+            "tag_vectors_line_up",
+            &[(
+                "families.csv",
+                "Roboto,/Quality/Drawing,90\nRoboto,/Expressive/Calm,20\n\
+                 Lora,/Quality/Drawing,40\nLora,wght@700,/Expressive/Calm,80\n",
+            )],
+        );
+        fs::write(
+            root.join("tags/tags_metadata.csv"),
+            "/Quality/Drawing,0,100,drawing quality\n",
+        )
+        .unwrap();
+        let gf = GoogleFonts::new(root, None);
+        assert_eq!(Some(vec![1.0, 0.9]), gf.tag_vector("Roboto"));
+        assert_eq!(Some(vec![-1.0, 0.4]), gf.tag_vector("Lora"));
+        assert_eq!(None, gf.tag_vector("Inter"));
     }
 
     #[test]
-    fn roboto_exemplar() {
+    fn designers_missing_from_catalog() {
+        let root = temp_repo_with_families(
+            "designers_missing_from_catalog",
+            &[
+                (
+                    "ofl/testsans",
+                    "name: \"Test Sans\" designer: \"Christian Robertson, Jane Doe\"",
+                ),
+                (
+                    "ofl/testserif",
+                    "name: \"Test Serif\" designer: \"Jane Doe\"",
+                ),
+            ],
+        );
+        let designer_dir = root.join("catalog/designers/christianrobertson");
+        fs::create_dir_all(&designer_dir).unwrap();
+        fs::write(
+            designer_dir.join("info.pb"),
+            "designer: \"Christian Robertson\"\nlink: \"\"\n",
+        )
+        .unwrap();
+        let gf = GoogleFonts::new(root, None);
+        let mut unresolved: Vec<_> = gf
+            .unresolved_designers()
+            .into_iter()
+            .map(|(f, name)| (f.name(), name))
+            .collect();
+        unresolved.sort();
+        assert_eq!(
+            vec![
+                ("Test Sans", "Jane Doe".to_string()),
+                ("Test Serif", "Jane Doe".to_string()),
+            ],
+            unresolved
+        );
+    }
+
+    #[test]
+    fn families_stream_as_json_lines() {
         let roboto = read_family(&testdata_file_content("roboto-metadata.pb")).unwrap();
-        let exemplar = exemplar(&roboto).unwrap();
-        assert_eq!("Roboto[wdth,wght].ttf", exemplar.filename());
+        let kosugi = read_family(&testdata_file_content("kosugimaru-metadata.pb")).unwrap();
+        let gf = GoogleFonts::from_families(vec![
+            (PathBuf::from("ofl/roboto/METADATA.pb"), roboto.clone()),
+            (PathBuf::from("ofl/kosugimaru/METADATA.pb"), kosugi.clone()),
+        ]);
+        let mut out = Vec::new();
+        gf.write_families_jsonl(&mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(2, lines.len());
+        assert!(lines[0].contains("\"date_added\":"));
+        assert_eq!(
+            vec![kosugi, roboto],
+            lines
+                .iter()
+                .map(|l| protobuf_json_mapping::parse_from_str::<FamilyProto>(l).unwrap())
+                .collect::<Vec<_>>()
+        );
     }
 
     #[test]
-    fn wix_exemplar() {
-        let roboto = read_family(&testdata_file_content("wixmadefortext-metadata.pb")).unwrap();
-        let exemplar = exemplar(&roboto).unwrap();
-        assert_eq!("WixMadeforText[wght].ttf", exemplar.filename());
+    fn nearest_width_names() {
+        let roboto = read_family(&testdata_file_content("roboto-metadata.pb")).unwrap();
+        assert_eq!(Some("Condensed"), nearest_width_name(&roboto, 50.0));
+        assert_eq!(Some("SemiCondensed"), nearest_width_name(&roboto, 85.0));
+        assert_eq!(Some("Normal"), nearest_width_name(&roboto, 150.0));
+
+        let statics = test_family(
+            r#"
+            fonts { filename: "TestSans-Regular.ttf" }
+            fonts { filename: "TestSans-SemiCondensed.ttf" }
+            fonts { filename: "TestSans-ExtraCondensed.ttf" }
+            "#,
+        );
+        assert_eq!(Some("SemiCondensed"), nearest_width_name(&statics, 80.0));
+        assert_eq!(Some("ExtraCondensed"), nearest_width_name(&statics, 50.0));
+        assert_eq!(
+            None,
+            nearest_width_name(
+                &test_family("fonts { filename: \"TestSans-Bold.ttf\" }"),
+                75.0
+            )
+        );
     }
 
     #[test]
-    fn parse_roboto_metadata() {
-        read_family(&testdata_file_content("roboto-metadata.pb")).unwrap();
+    fn exemplar_stability_without_variable_fonts() {
+        let gf = GoogleFonts::new(PathBuf::from("/nonexistent"), None);
+        let roboto = read_family(&testdata_file_content("roboto-metadata.pb")).unwrap();
+        assert_eq!(
+            ExemplarStability::NoStaticFallback,
+            gf.exemplar_stability(&roboto)
+        );
+        let kosugi = read_family(&testdata_file_content("kosugimaru-metadata.pb")).unwrap();
+        assert_eq!(ExemplarStability::Stable, gf.exemplar_stability(&kosugi));
+        let mixed = test_family(
+            r#"
+            fonts { weight: 400 filename: "TestSans[wght].ttf" }
+            fonts { weight: 700 filename: "TestSans-Bold.ttf" }
+            "#,
+        );
+        assert_eq!(
+            ExemplarStability::ChangesWithoutVariable,
+            gf.exemplar_stability(&mixed)
+        );
     }
 
     #[test]
-    fn parse_wix_metadata() {
-        // Has the undocumented position field
-        read_family(&testdata_file_content("wixmadefortext-metadata.pb")).unwrap();
+    fn coverage_delta_between_families() {
+        let gf = GoogleFonts::new(PathBuf::from("/nonexistent"), None);
+        let roboto = read_family(&testdata_file_content("roboto-metadata.pb")).unwrap();
+        let kosugi = read_family(&testdata_file_content("kosugimaru-metadata.pb")).unwrap();
+        let delta = gf.coverage_delta(&roboto, &kosugi);
+        assert_eq!(
+            CoverageDelta {
+                lost_subsets: vec![
+                    "cyrillic-ext".to_string(),
+                    "greek".to_string(),
+                    "greek-ext".to_string(),
+                    "math".to_string(),
+                    "symbols".to_string(),
+                    "vietnamese".to_string(),
+                ],
+                gained_subsets: vec!["japanese".to_string()],
+                lost_scripts: vec!["Grek".to_string()],
+                gained_scripts: vec!["Jpan".to_string()],
+            },
+            delta
+        );
+        assert_eq!(
+            CoverageDelta::default(),
+            gf.coverage_delta(&roboto, &roboto)
+        );
     }
 
     #[test]
-    fn parse_primary_lang_script_metadata() {
-        let family = read_family(&testdata_file_content("kosugimaru-metadata.pb")).unwrap();
+    fn axis_declarations_checked_against_filenames() {
+        let roboto = read_family(&testdata_file_content("roboto-metadata.pb")).unwrap();
+        let gf = GoogleFonts::from_families(vec![
+            (PathBuf::from("ofl/roboto/METADATA.pb"), roboto),
+            (
+                PathBuf::from("ofl/a/METADATA.pb"),
+                test_family("name: \"A\" fonts { filename: \"A[wght].ttf\" }"),
+            ),
+            (
+                PathBuf::from("ofl/b/METADATA.pb"),
+                test_family(
+                    "name: \"B\" fonts { filename: \"B-Regular.ttf\" } \
+                     axes { tag: \"wght\" min_value: 100 max_value: 900 }",
+                ),
+            ),
+            (
+                PathBuf::from("ofl/c/METADATA.pb"),
+                test_family(
+                    "name: \"C\" fonts { filename: \"C[wdth,wght].ttf\" } \
+                     axes { tag: \"wght\" min_value: 100 max_value: 900 }",
+                ),
+            ),
+        ]);
         assert_eq!(
-            ("Jpan", "Invalid"),
-            (family.primary_script(), family.primary_language())
+            vec![
+                ("A", "A[wght].ttf is variable but no axes are declared".to_string()),
+                ("B", "axes declared but no variable font file".to_string()),
+                (
+                    "C",
+                    "C[wdth,wght].ttf encodes axes {\"wdth\", \"wght\"} but {\"wght\"} are declared"
+                        .to_string()
+                ),
+            ],
+            gf.axis_declaration_mismatches()
+                .into_iter()
+                .map(|(_, f, problem)| (f.name(), problem))
+                .collect::<Vec<_>>()
         );
     }
 
     #[test]
-    fn parse_tag3() {
-        Tagging::from_str("Roboto Slab, /quant/stroke_width_min, 26.31").expect("To parse");
+    fn sample_text_fitting_width() {
+        let gf = GoogleFonts::new(PathBuf::from("/nonexistent"), None);
+        let family = test_family("name: \"Test Sans\" primary_language: \"en_Latn\"");
+        let english = gf.language("en_Latn").unwrap();
+        let fields = sample_text_fields(english);
+        let longest = fields.iter().map(|s| s.chars().count()).max().unwrap();
+        let shortest = fields.iter().map(|s| s.chars().count()).min().unwrap();
+
+        let roomy = gf.sample_text_fitting(&family, longest).unwrap();
+        assert_eq!(longest, roomy.chars().count());
+        let fitting = gf.sample_text_fitting(&family, shortest + 1).unwrap();
+        assert!(fields.contains(&fitting.as_str()));
+        assert!(fitting.chars().count() <= shortest + 1);
+        let truncated = gf.sample_text_fitting(&family, 3).unwrap();
+        assert_eq!(3, truncated.chars().count());
+        assert!(fields.iter().any(|s| s.starts_with(&truncated)));
     }
 
     #[test]
-    fn parse_tag4() {
-        Tagging::from_str("Roboto Slab, wght@100, /quant/stroke_width_min, 26.31")
-            .expect("To parse");
+    fn families_grouped_by_license() {
+        let roboto = read_family(&testdata_file_content("roboto-metadata.pb")).unwrap();
+        let kosugi = read_family(&testdata_file_content("kosugimaru-metadata.pb")).unwrap();
+        let gf = GoogleFonts::from_families(vec![
+            (PathBuf::from("ofl/roboto/METADATA.pb"), roboto),
+            (PathBuf::from("apache/kosugimaru/METADATA.pb"), kosugi),
+            (
+                PathBuf::from("ofl/testsans/METADATA.pb"),
+                test_family("name: \"Test Sans\" license: \"OFL\""),
+            ),
+        ]);
+        let groups: Vec<(String, Vec<&str>)> = gf
+            .families_by_license()
+            .into_iter()
+            .map(|(license, families)| (license, families.iter().map(|f| f.name()).collect()))
+            .collect();
+        assert_eq!(
+            vec![
+                ("APACHE2".to_string(), vec!["Kosugi Maru"]),
+                ("OFL".to_string(), vec!["Roboto", "Test Sans"]),
+            ],
+            groups
+        );
     }
 
     #[test]
-    fn parse_tag_quoted() {
-        Tagging::from_str("Georama, \"ital,wght@1,100\", /quant/stroke_width_min, 16.97")
-            .expect("To parse");
+    #[cfg(feature = "skrifa")]
+    fn computed_languages_from_cmap() {
+        let root = temp_repo_with_families(
+            "computed_languages_from_cmap",
+            &[("ofl/testsans", "name: \"Test Sans\" fonts {}")],
+        );
+        let gf = GoogleFonts::new(root.clone(), None);
+        let (_, family) = gf.family_by_name("Test Sans").unwrap();
+        assert!(gf.computed_languages(family).is_err());
+
+        fs::copy(
+            testdata_dir().join("TestSans-Regular.ttf"),
+            root.join("ofl/testsans/TestSans-400.ttf"),
+        )
+        .unwrap();
+        let languages = gf.computed_languages(family).unwrap();
+        let ids: Vec<&str> = languages.iter().map(|l| l.id()).collect();
+        assert!(ids.contains(&"en_Latn"));
+        assert!(ids.contains(&"es_Latn"));
+        assert!(!ids.contains(&"ru_Cyrl"));
+        assert!(!ids.contains(&"vi_Latn"));
+        assert!(ids.windows(2).all(|w| w[0] < w[1]));
     }
 
+    #[cfg(feature = "serde")]
     #[test]
-    fn parse_tag_quoted2() {
-        Tagging::from_str("\"\",t,1").expect("To parse");
+    fn load_index_skips_rescanning() {
+        let root = temp_repo_with_families(
+            "load_index_skips_rescanning",
+            &[
+                ("ofl/roboto", "name: \"Roboto\"\nfonts {}"),
+                ("ofl/broken", "fonts { weight: \"heavy\" }"),
+            ],
+        );
+        fs::write(
+            root.join("tags/all/families.csv"),
+            "Roboto,/Quality/Drawing,90\n",
+        )
+        .unwrap();
+        let mut index = Vec::new();
+        GoogleFonts::new(root.clone(), None)
+            .dump_index(&mut index)
+            .unwrap();
+        fs::remove_dir_all(root.join("ofl/roboto")).unwrap();
+        fs::remove_dir_all(root.join("tags")).unwrap();
+
+        let gf = GoogleFonts::new(root.clone(), None);
+        gf.load_index(index.as_slice()).unwrap();
+        let names: Vec<_> = gf
+            .families()
+            .iter()
+            .map(|e| e.family().map(|f| f.name()))
+            .collect();
+        assert_eq!(names, vec![None, Some("Roboto")]);
+        assert_eq!(gf.families()[1].path, root.join("ofl/roboto/METADATA.pb"));
+        assert_eq!(gf.tags().unwrap().len(), 1);
+        assert!(gf.tag_metadata().is_err());
+
+        let filtered = GoogleFonts::new(root, Some(Regex::new("broken").unwrap()));
+        filtered.load_index(index.as_slice()).unwrap();
+        assert_eq!(filtered.families().len(), 1);
+    }
+
+    #[test]
+    fn font_is_variable() {
+        let roboto = read_family(&testdata_file_content("roboto-metadata.pb")).unwrap();
+        assert!(roboto.fonts[0].is_variable());
+        let mut font = FontProto::new();
+        for (filename, variable) in [
+            ("Roboto-Regular.ttf", false),
+            ("Roboto-Italic[wght].ttf", true),
+            ("Roboto[].ttf", false),
+        ] {
+            font.set_filename(filename.to_string());
+            assert_eq!(variable, font.is_variable(), "{filename}");
+        }
+    }
+
+    #[test]
+    fn family_errors_name_the_metadata() {
+        let root = temp_repo_with_families(
+            "family_errors_name_the_metadata",
+            &[("ofl/broken", "fonts { weight: \"heavy\" }")],
+        );
+        let gf = GoogleFonts::new(root.clone(), None);
+        let entry = &gf.families()[0];
+        let path = root.join("ofl/broken/METADATA.pb");
+        let Err(GfError::Parse {
+            path: error_path,
+            error,
+        }) = &entry.family
+        else {
+            panic!("expected a parse error, got {:?}", entry.family);
+        };
+        assert_eq!(&path, error_path);
+        let message = entry.family.as_ref().unwrap_err().to_string();
+        assert!(message.contains(&path.display().to_string()), "{message}");
+        assert!(message.contains(&error.to_string()), "{message}");
+    }
+
+    #[test]
+    fn family_by_name_keeps_first_path() {
+        let root = temp_repo_with_families(
+            "family_by_name_keeps_first_path",
+            &[
+                ("ofl/b", "name: \"Twin\" designer: \"B\""),
+                ("ofl/a", "name: \"Twin\" designer: \"A\""),
+                ("ofl/c", "name: \"Twin\" designer: \"C\""),
+            ],
+        );
+        let gf = GoogleFonts::new(root.clone(), None);
+        let (path, family) = gf.family_by_name("Twin").unwrap();
+        assert_eq!(root.join("ofl/a/METADATA.pb"), path);
+        assert_eq!("A", family.designer());
+        let pairs: Vec<_> = gf.families_by_name().collect();
+        assert_eq!(1, pairs.len());
+        assert_eq!("A", pairs[0].1.designer());
     }
 }
@@ -0,0 +1,56 @@
+//! Enums for the string-valued fields of `METADATA.pb` that have a known set
+//! of values.
+
+/// Declare an enum with a variant per known `METADATA.pb` value plus an
+/// `Other(String)` catch-all, so parsing never fails.
+///
+/// Each variant is paired with the value as written, e.g. `SansSerif =
+/// "SANS_SERIF"`. The enum gets `as_str`, `FromStr` and `Display`; parsing
+/// ignores case and surrounding whitespace and treats spaces and hyphens as
+/// underscores.
+macro_rules! metadata_enum {
+    (
+        $(#[$meta:meta])*
+        pub enum $name:ident {
+            $($(#[$variant_meta:meta])* $variant:ident = $value:literal,)*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+        pub enum $name {
+            $($(#[$variant_meta])* $variant,)*
+            /// Any other value, kept exactly as written.
+            Other(String),
+        }
+
+        impl $name {
+            /// The value as written in `METADATA.pb`.
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $($name::$variant => $value,)*
+                    $name::Other(name) => name,
+                }
+            }
+        }
+
+        impl std::str::FromStr for $name {
+            type Err = std::convert::Infallible;
+
+            /// Parse a value, ignoring case and treating spaces and hyphens
+            /// as underscores, so "Sans Serif" matches `SANS_SERIF`.
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                let normalized = s.trim().to_ascii_uppercase().replace([' ', '-'], "_");
+                Ok(match normalized.as_str() {
+                    $($value => $name::$variant,)*
+                    _ => $name::Other(s.to_string()),
+                })
+            }
+        }
+
+        impl std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+    };
+}
@@ -0,0 +1,131 @@
+//! A small bundled copy of the Google Fonts axis registry.
+
+use crate::AxisProto;
+
+/// Display defaults for a registered axis.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct RegisteredAxis {
+    /// Axis tag, e.g. `wght`
+    pub tag: &'static str,
+    /// Display name, e.g. "Weight"
+    pub name: &'static str,
+    /// Lowest value the registry permits
+    pub min_value: f32,
+    /// Default position
+    pub default_value: f32,
+    /// Highest value the registry permits
+    pub max_value: f32,
+    /// Named positions on the axis, e.g. ("Bold", 700.0), in ascending order
+    pub fallbacks: &'static [(&'static str, f32)],
+}
+
+const REGISTERED_AXES: [RegisteredAxis; 6] = [
+    RegisteredAxis {
+        tag: "wght",
+        name: "Weight",
+        min_value: 1.0,
+        default_value: 400.0,
+        max_value: 1000.0,
+        fallbacks: &[
+            ("Thin", 100.0),
+            ("ExtraLight", 200.0),
+            ("Light", 300.0),
+            ("Regular", 400.0),
+            ("Medium", 500.0),
+            ("SemiBold", 600.0),
+            ("Bold", 700.0),
+            ("ExtraBold", 800.0),
+            ("Black", 900.0),
+        ],
+    },
+    RegisteredAxis {
+        tag: "wdth",
+        name: "Width",
+        min_value: 25.0,
+        default_value: 100.0,
+        max_value: 200.0,
+        fallbacks: &[
+            ("UltraCondensed", 50.0),
+            ("ExtraCondensed", 62.5),
+            ("Condensed", 75.0),
+            ("SemiCondensed", 87.5),
+            ("Normal", 100.0),
+            ("SemiExpanded", 112.5),
+            ("Expanded", 125.0),
+            ("ExtraExpanded", 150.0),
+            ("UltraExpanded", 200.0),
+        ],
+    },
+    RegisteredAxis {
+        tag: "slnt",
+        name: "Slant",
+        min_value: -90.0,
+        default_value: 0.0,
+        max_value: 90.0,
+        fallbacks: &[],
+    },
+    RegisteredAxis {
+        tag: "ital",
+        name: "Italic",
+        min_value: 0.0,
+        default_value: 0.0,
+        max_value: 1.0,
+        fallbacks: &[("Roman", 0.0), ("Italic", 1.0)],
+    },
+    RegisteredAxis {
+        tag: "opsz",
+        name: "Optical size",
+        min_value: 5.0,
+        default_value: 14.0,
+        max_value: 1200.0,
+        fallbacks: &[],
+    },
+    RegisteredAxis {
+        tag: "GRAD",
+        name: "Grade",
+        min_value: -1000.0,
+        default_value: 0.0,
+        max_value: 1000.0,
+        fallbacks: &[],
+    },
+];
+
+/// Look up the registry defaults for an axis tag.
+///
+/// Covers the five OpenType-registered axes (`wght`, `wdth`, `slnt`, `ital`,
+/// `opsz`) plus `GRAD`. Tags are matched case-sensitively.
+pub fn registered_axis(tag: &str) -> Option<RegisteredAxis> {
+    REGISTERED_AXES.iter().find(|a| a.tag == tag).copied()
+}
+
+/// The registry default for an axis tag, e.g. 400 for `wght` or 14 for `opsz`.
+///
+/// A shorthand for [`registered_axis`]`(tag).default_value`.
+pub fn registered_axis_default(tag: &str) -> Option<f32> {
+    registered_axis(tag).map(|a| a.default_value)
+}
+
+impl AxisProto {
+    /// The fallback name given for exactly `value`, e.g. "Bold" for `wght` 700.
+    pub fn fallback_name(&self, value: f32) -> Option<&str> {
+        self.fallback
+            .iter()
+            .find(|f| f.value() == value)
+            .map(|f| f.name())
+    }
+
+    /// The fallback name whose value is closest to `value`.
+    ///
+    /// Ties go to the fallback listed first. Returns `None` only if the axis
+    /// has no fallbacks.
+    pub fn nearest_fallback_name(&self, value: f32) -> Option<&str> {
+        self.fallback
+            .iter()
+            .min_by(|a, b| {
+                (a.value() - value)
+                    .abs()
+                    .total_cmp(&(b.value() - value).abs())
+            })
+            .map(|f| f.name())
+    }
+}
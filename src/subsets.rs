@@ -0,0 +1,235 @@
+//! Knowledge about the subsets used in Google Fonts metadata.
+
+use std::{
+    convert::Infallible,
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+/// Map a Google Fonts subset name to the ISO 15924 script code it covers.
+///
+/// Returns `None` for subsets that don't correspond to a single writing
+/// system (e.g. `menu`, `math`, `symbols`) or that aren't known.
+pub fn subset_script(subset: &str) -> Option<&'static str> {
+    let script = match subset {
+        "latin" | "latin-ext" | "vietnamese" => "Latn",
+        "cyrillic" | "cyrillic-ext" => "Cyrl",
+        "greek" | "greek-ext" => "Grek",
+        "arabic" => "Arab",
+        "armenian" => "Armn",
+        "bengali" => "Beng",
+        "cherokee" => "Cher",
+        "chinese-simplified" => "Hans",
+        "chinese-traditional" | "chinese-hongkong" => "Hant",
+        "devanagari" => "Deva",
+        "ethiopic" => "Ethi",
+        "georgian" => "Geor",
+        "gujarati" => "Gujr",
+        "gurmukhi" => "Guru",
+        "hebrew" => "Hebr",
+        "japanese" => "Jpan",
+        "kannada" => "Knda",
+        "khmer" => "Khmr",
+        "korean" => "Kore",
+        "lao" => "Laoo",
+        "malayalam" => "Mlym",
+        "mongolian" => "Mong",
+        "myanmar" => "Mymr",
+        "oriya" => "Orya",
+        "sinhala" => "Sinh",
+        "tamil" => "Taml",
+        "telugu" => "Telu",
+        "thaana" => "Thaa",
+        "thai" => "Thai",
+        "tibetan" => "Tibt",
+        _ => return None,
+    };
+    Some(script)
+}
+
+static SUBSET_UNICODE_RANGES: [(&str, &str); 16] = [
+    (
+        "latin",
+        "U+0000-00FF, U+0131, U+0152-0153, U+02BB-02BC, U+02C6, U+02DA, U+02DC, U+0304, \
+         U+0308, U+0329, U+2000-206F, U+20AC, U+2122, U+2191, U+2193, U+2212, U+2215, \
+         U+FEFF, U+FFFD",
+    ),
+    (
+        "latin-ext",
+        "U+0100-02BA, U+02BD-02C5, U+02C7-02CC, U+02CE-02D7, U+02DD-02FF, U+0304, U+0308, \
+         U+0329, U+1D00-1DBF, U+1E00-1E9F, U+1EF2-1EFF, U+2020, U+20A0-20AB, U+20AD-20C0, \
+         U+2113, U+2C60-2C7F, U+A720-A7FF",
+    ),
+    (
+        "vietnamese",
+        "U+0102-0103, U+0110-0111, U+0128-0129, U+0168-0169, U+01A0-01A1, U+01AF-01B0, \
+         U+0300-0301, U+0303-0304, U+0308-0309, U+0323, U+0329, U+1EA0-1EF9, U+20AB",
+    ),
+    (
+        "cyrillic",
+        "U+0301, U+0400-045F, U+0490-0491, U+04B0-04B1, U+2116",
+    ),
+    (
+        "cyrillic-ext",
+        "U+0460-052F, U+1C80-1C8A, U+20B4, U+2DE0-2DFF, U+A640-A69F, U+FE2E-FE2F",
+    ),
+    (
+        "greek",
+        "U+0370-0377, U+037A-037F, U+0384-038A, U+038C, U+038E-03A1, U+03A3-03FF",
+    ),
+    ("greek-ext", "U+1F00-1FFF"),
+    (
+        "arabic",
+        "U+0600-06FF, U+0750-077F, U+0870-088E, U+0890-0891, U+0897-08E1, U+08E3-08FF, \
+         U+200C-200E, U+2010-2011, U+204F, U+2E41, U+FB50-FDFF, U+FE70-FE74, U+FE76-FEFC",
+    ),
+    (
+        "hebrew",
+        "U+0307-0308, U+0590-05FF, U+200C-2010, U+20AA, U+25CC, U+FB1D-FB4F",
+    ),
+    (
+        "armenian",
+        "U+0308, U+0530-058F, U+2010, U+2024, U+25CC, U+FB13-FB17",
+    ),
+    (
+        "georgian",
+        "U+0589, U+10A0-10FF, U+1C90-1CBA, U+1CBD-1CBF, U+205A, U+2D00-2D2F, U+2E31",
+    ),
+    (
+        "devanagari",
+        "U+0900-097F, U+1CD0-1CF9, U+200C-200D, U+20A8, U+20B9, U+20F0, U+25CC, \
+         U+A830-A839, U+A8E0-A8FF, U+11B00-11B09",
+    ),
+    (
+        "bengali",
+        "U+0951-0952, U+0964-0965, U+0980-09FE, U+1CD0, U+1CD2, U+1CD5-1CD6, U+1CD8, \
+         U+1CE1, U+1CEA, U+1CED, U+1CF2, U+1CF5-1CF7, U+200C-200D, U+20B9, U+25CC, U+A8F1",
+    ),
+    (
+        "tamil",
+        "U+0964-0965, U+0B82-0BFA, U+200C-200D, U+20B9, U+25CC",
+    ),
+    (
+        "thai",
+        "U+02D7, U+0303, U+0331, U+0E01-0E5B, U+200C-200D, U+25CC",
+    ),
+    ("khmer", "U+1780-17FF, U+19E0-19FF, U+200C-200D, U+25CC"),
+];
+
+/// The CSS `unicode-range` Google Fonts serves for a subset, e.g.
+/// `U+0370-0377, ...` for `greek`.
+///
+/// Returns `None` for `menu`, for subsets that Google Fonts slices into many
+/// ranges (Chinese, Japanese, Korean, `math`, `symbols`) and for unknown
+/// subsets.
+pub fn subset_unicode_range(subset: &str) -> Option<&'static str> {
+    SUBSET_UNICODE_RANGES
+        .iter()
+        .find(|(name, _)| *name == subset)
+        .map(|(_, range)| *range)
+}
+
+/// The subsets [`subset_unicode_range`] knows a range for.
+#[cfg(feature = "skrifa")]
+pub(crate) fn ranged_subsets() -> impl Iterator<Item = &'static str> {
+    SUBSET_UNICODE_RANGES.iter().map(|(name, _)| *name)
+}
+
+/// The codepoints a `unicode-range` value such as `U+0000-00FF, U+0131`
+/// covers. Malformed entries are skipped.
+#[cfg(feature = "skrifa")]
+pub(crate) fn unicode_range_codepoints(range: &str) -> impl Iterator<Item = u32> + '_ {
+    range
+        .split(',')
+        .filter_map(|part| {
+            let part = part.trim().trim_start_matches("U+");
+            let (start, end) = part.split_once('-').unwrap_or((part, part));
+            Some(u32::from_str_radix(start, 16).ok()?..=u32::from_str_radix(end, 16).ok()?)
+        })
+        .flatten()
+}
+
+/// A Google Fonts subset name.
+///
+/// Well-known subsets get their own variant; anything else is kept as
+/// `Other`, so parsing never fails.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Subset {
+    Latin,
+    LatinExt,
+    Cyrillic,
+    CyrillicExt,
+    Greek,
+    GreekExt,
+    Vietnamese,
+    Arabic,
+    Hebrew,
+    Devanagari,
+    Japanese,
+    Korean,
+    ChineseSimplified,
+    ChineseTraditional,
+    Menu,
+    Math,
+    Symbols,
+    Other(String),
+}
+
+static KNOWN_SUBSETS: [(&str, Subset); 17] = [
+    ("latin", Subset::Latin),
+    ("latin-ext", Subset::LatinExt),
+    ("cyrillic", Subset::Cyrillic),
+    ("cyrillic-ext", Subset::CyrillicExt),
+    ("greek", Subset::Greek),
+    ("greek-ext", Subset::GreekExt),
+    ("vietnamese", Subset::Vietnamese),
+    ("arabic", Subset::Arabic),
+    ("hebrew", Subset::Hebrew),
+    ("devanagari", Subset::Devanagari),
+    ("japanese", Subset::Japanese),
+    ("korean", Subset::Korean),
+    ("chinese-simplified", Subset::ChineseSimplified),
+    ("chinese-traditional", Subset::ChineseTraditional),
+    ("menu", Subset::Menu),
+    ("math", Subset::Math),
+    ("symbols", Subset::Symbols),
+];
+
+impl Subset {
+    /// The subset name as written in `METADATA.pb`, e.g. `latin-ext`.
+    pub fn as_str(&self) -> &str {
+        match self {
+            Subset::Other(name) => name,
+            known => {
+                KNOWN_SUBSETS
+                    .iter()
+                    .find(|(_, s)| s == known)
+                    .expect("Every known subset has a name")
+                    .0
+            }
+        }
+    }
+
+    /// The ISO 15924 script the subset covers; see [`subset_script`].
+    pub fn script(&self) -> Option<&'static str> {
+        subset_script(self.as_str())
+    }
+}
+
+impl FromStr for Subset {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(KNOWN_SUBSETS
+            .iter()
+            .find(|(name, _)| *name == s)
+            .map(|(_, subset)| subset.clone())
+            .unwrap_or_else(|| Subset::Other(s.to_string())))
+    }
+}
+
+impl Display for Subset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}